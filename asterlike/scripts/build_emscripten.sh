@@ -31,9 +31,13 @@ rustup target add "$TARGET" >/dev/null 2>&1 || true
 
 # Importante: `--features emscripten` compila SDL3 (+image/+ttf) desde source
 # usando el toolchain de Emscripten, resolviendo el error de -lSDL3*.
+# El audio (SDL3_mixer) queda fuera por defecto y el juego corre mudo;
+# para incluirlo: FEATURES="emscripten audio".
+FEATURES="${FEATURES:-emscripten}"
 (
   cd "$ROOT_DIR"
-  cargo build --target "$TARGET" "${CARGO_PROFILE_FLAGS[@]}" --features emscripten
+  cargo build --target "$TARGET" "${CARGO_PROFILE_FLAGS[@]}" \
+    --no-default-features --features "$FEATURES"
 )
 
 OUT_DIR="$ROOT_DIR/target/$TARGET/$TARGET_SUBDIR/deps"
@@ -0,0 +1,83 @@
+use crate::{
+    font::{Font, GlyphMap},
+    wrappers,
+};
+use sdl3::{
+    render::{Texture, TextureCreator},
+    video::WindowContext,
+};
+
+/// Color made transparent in every texture
+const COLOR_KEY: (u8, u8, u8) = (0x0, 0x0, 0x0);
+
+/// Handle of a texture owned by `Assets`
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum TextureId {
+    SpriteSheet,
+    Font,
+}
+
+impl TextureId {
+    /// Every texture, in declaration order
+    const ALL: [TextureId; 2] = [TextureId::SpriteSheet, TextureId::Font];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            TextureId::SpriteSheet => "assets/img/sprite-sheet.bmp",
+            TextureId::Font => "assets/fonts/fontx64.bmp",
+        }
+    }
+
+    pub fn color_key(self) -> (u8, u8, u8) {
+        COLOR_KEY
+    }
+}
+
+/// Owner of the textures of the game, looked up by `TextureId`
+pub struct Assets {
+    creator: TextureCreator<WindowContext>,
+    textures: Vec<Texture>,
+    font: Font,
+}
+
+impl Assets {
+    pub fn new(creator: TextureCreator<WindowContext>) -> Self {
+        let textures = Assets::load(&creator);
+        let font = Font::new(TextureId::Font, GlyphMap::ascii(12, (64, 64), 32), 1.0);
+
+        Assets {
+            creator,
+            textures,
+            font,
+        }
+    }
+
+    fn load(creator: &TextureCreator<WindowContext>) -> Vec<Texture> {
+        TextureId::ALL
+            .iter()
+            .map(|id| wrappers::load_texture(id.path(), creator, id.color_key()))
+            .collect()
+    }
+
+    pub fn get(&self, id: TextureId) -> &Texture {
+        &self.textures[id as usize]
+    }
+
+    pub fn get_mut(&mut self, id: TextureId) -> &mut Texture {
+        &mut self.textures[id as usize]
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Create every texture again, after the renderer lost them
+    pub fn reload(&mut self) {
+        let textures = Assets::load(&self.creator);
+
+        for texture in std::mem::replace(&mut self.textures, textures) {
+            // SAFETY: nothing refers to the old textures once replaced
+            unsafe { texture.destroy() };
+        }
+    }
+}
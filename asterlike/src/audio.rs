@@ -0,0 +1,135 @@
+#[cfg(feature = "audio")]
+use crate::global::Options;
+
+/// Sound effects played by the game
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Sound {
+    Shoot,
+    Hit,
+    Damage,
+    GameOver,
+    PowerUp,
+}
+
+#[cfg(feature = "audio")]
+impl Sound {
+    /// Every sound, in declaration order
+    const ALL: [Sound; 5] = [
+        Sound::Shoot,
+        Sound::Hit,
+        Sound::Damage,
+        Sound::GameOver,
+        Sound::PowerUp,
+    ];
+
+    fn path(self) -> &'static str {
+        match self {
+            Sound::Shoot => "assets/sfx/shoot.wav",
+            Sound::Hit => "assets/sfx/hit.wav",
+            Sound::Damage => "assets/sfx/damage.wav",
+            Sound::GameOver => "assets/sfx/game-over.wav",
+            Sound::PowerUp => "assets/sfx/power-up.wav",
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+const MUSIC_PATH: &str = "assets/sfx/music.wav";
+
+#[cfg(feature = "audio")]
+struct Mixer {
+    _audio: sdl3::AudioSubsystem,
+    device: sdl3::mixer::Mixer,
+    sounds: Vec<sdl3::mixer::Audio>,
+    music: sdl3::mixer::Audio,
+}
+
+/// Sound effects and music, silent when no audio device is available
+pub struct Audio {
+    #[cfg(feature = "audio")]
+    mixer: Option<Mixer>,
+}
+
+#[cfg(feature = "audio")]
+impl Audio {
+    pub fn new(context: &sdl3::Sdl, volume: u8) -> Self {
+        let mixer = Audio::open(context)
+            .inspect_err(|err| eprintln!("audio disabled: {err}"))
+            .ok();
+        let mut audio = Audio { mixer };
+
+        audio.set_volume(volume);
+        audio
+    }
+
+    fn open(context: &sdl3::Sdl) -> Result<Mixer, sdl3::Error> {
+        let audio = context.audio()?;
+        let device = sdl3::mixer::Mixer::open_device(None)?;
+
+        // Short effects are decoded up front, the music while it plays
+        let sounds = Sound::ALL
+            .iter()
+            .map(|sound| device.load_audio(sound.path(), true))
+            .collect::<Result<_, _>>()?;
+        let music = device.load_audio(MUSIC_PATH, false)?;
+
+        Ok(Mixer {
+            _audio: audio,
+            device,
+            sounds,
+            music,
+        })
+    }
+
+    pub fn play(&self, sound: Sound) {
+        if let Some(mixer) = &self.mixer {
+            // A sound that fails to start is only dropped
+            let _ = mixer.device.play_audio(&mixer.sounds[sound as usize]);
+        }
+    }
+
+    /// Start the background music, looping forever
+    pub fn play_music(&self) {
+        let Some(mixer) = &self.mixer else {
+            return;
+        };
+
+        let result = mixer.device.create_track().and_then(|track| {
+            track.set_audio(&mixer.music)?;
+            track.set_loops(-1)?;
+            track.play()?;
+            // The mixer destroys its tracks when closed, dropping the track
+            // here would stop the music
+            std::mem::forget(track);
+            Ok(())
+        });
+
+        if let Err(err) = result {
+            eprintln!("unable to play music: {err}");
+        }
+    }
+
+    /// Set the volume of sounds and music, from 0 to `Options::MAX_VOLUME`
+    pub fn set_volume(&mut self, volume: u8) {
+        if let Some(mixer) = &self.mixer {
+            let gain = f32::from(volume) / f32::from(Options::MAX_VOLUME);
+            if let Err(err) = mixer.device.set_gain(gain) {
+                eprintln!("unable to set the volume: {err}");
+            }
+        }
+    }
+}
+
+/// Without the `audio` feature every call is a no-op
+#[cfg(not(feature = "audio"))]
+impl Audio {
+    pub fn new(_context: &sdl3::Sdl, _volume: u8) -> Self {
+        Audio {}
+    }
+
+    pub fn play(&self, _sound: Sound) {}
+
+    pub fn play_music(&self) {}
+
+    pub fn set_volume(&mut self, _volume: u8) {}
+}
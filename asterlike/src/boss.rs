@@ -0,0 +1,234 @@
+use crate::{
+    collision::Collider,
+    enemy::{SHOT_SPEED, Shot, aim, nearest_ship},
+    global::{STEP, Screen},
+    object::{Object, Ship},
+};
+use sdl3::{
+    rect::Rect,
+    render::{Texture, WindowCanvas},
+};
+use std::f32::consts::TAU;
+
+/// Speed of the boss moving into view, in pixels per second
+const ENTRY_SPEED: f32 = 60.0;
+
+/// Distance below the HUD at which the boss stops coming down, in pixels
+const HOVER: f32 = 16.0;
+
+/// Horizontal reach of the boss swaying from side to side, in pixels
+const SWAY: f32 = 100.0;
+
+/// Size the hull is drawn at, relative to its sprite
+const HULL_SCALE: f32 = 2.0;
+
+/// Where the weak points sit, relative to the top left corner of the hull
+const WEAK_POINTS: [(f32, f32); 3] = [(32.0, 64.0), (128.0, 64.0), (80.0, 128.0)];
+
+/// Lives of each weak point of the first boss, and the ones added for each
+/// boss defeated before
+const WEAK_POINT_LIVES: i16 = 12;
+const LIVES_PER_BOSS: i16 = 6;
+
+/// Bullets fired at once by the fan and ring attacks
+const FAN_SHOTS: usize = 5;
+const RING_SHOTS: usize = 12;
+
+/// Angle between two bullets of the fan attack, in radians
+const FAN_SPREAD: f32 = 0.3;
+
+/// Attacks of the boss, harder as its weak points are destroyed
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Phase {
+    /// Every weak point fires at the nearest ship
+    Aimed,
+    /// A fan of bullets down the screen
+    Fan,
+    /// A turning ring of bullets in every direction, while swaying faster
+    Ring,
+}
+
+impl Phase {
+    /// Time between two attacks, in seconds
+    fn cooldown(self) -> f64 {
+        match self {
+            Phase::Aimed => 1.2,
+            Phase::Fan => 1.4,
+            Phase::Ring => 1.0,
+        }
+    }
+
+    /// Angular speed of the sway, in radians per second
+    fn sway(self) -> f32 {
+        match self {
+            Phase::Aimed => 0.8,
+            Phase::Fan => 1.2,
+            Phase::Ring => 2.0,
+        }
+    }
+}
+
+/// Armored body of the boss, stops bullets without taking damage
+pub struct Hull;
+
+/// Part of the boss that takes damage, the boss is defeated once all of
+/// them are destroyed
+pub struct WeakPoint {
+    pub(crate) lives: i16,
+    /// Position relative to the hull
+    offset: (f32, f32),
+}
+
+/// A large asteroid carrying turrets, fought at milestone scores
+pub struct Boss {
+    pub(crate) hull: Object<Hull>,
+    pub(crate) weak_points: Vec<Object<WeakPoint>>,
+    /// Lives of all the weak points together when the boss arrived
+    max_health: i16,
+    /// Angle of the sway, in radians
+    sway: f32,
+    /// Time at which the boss attacks again
+    reload: f64,
+}
+
+impl Boss {
+    /// Create a boss above the middle of the screen, tougher for each boss
+    /// defeated before
+    pub fn new(screen: Screen, defeated: u32, now: f64) -> Self {
+        let mut hull = Object::new(Hull, 0, Rect::new(96, 32, 96, 96), HULL_SCALE);
+        let size = hull.size();
+        hull.pos = ((screen.width as f32 - size) / 2.0, -size);
+        hull.collider = Collider::Circle;
+
+        let lives = WEAK_POINT_LIVES + LIVES_PER_BOSS * defeated.min(10) as i16;
+        let weak_points = WEAK_POINTS
+            .iter()
+            .map(|&offset| {
+                let mut obj = Object::new(
+                    WeakPoint { lives, offset },
+                    0,
+                    Rect::new(384, 0, 32, 32),
+                    1.0,
+                );
+                obj.collider = Collider::Pixel;
+                obj
+            })
+            .collect();
+
+        let mut boss = Boss {
+            hull,
+            weak_points,
+            max_health: lives * WEAK_POINTS.len() as i16,
+            sway: 0.0,
+            reload: now,
+        };
+        boss.place();
+        boss
+    }
+
+    /// Fraction of the health left, shown in the HUD
+    pub fn health(&self) -> f32 {
+        let lives: i16 = self.weak_points.iter().map(|obj| obj.lives.max(0)).sum();
+        lives as f32 / self.max_health as f32
+    }
+
+    /// Check if every weak point is destroyed
+    pub fn is_defeated(&self) -> bool {
+        self.weak_points.iter().all(|obj| obj.lives <= 0)
+    }
+
+    /// Current attack, from the number of weak points left
+    pub fn phase(&self) -> Phase {
+        match self.weak_points.iter().filter(|obj| obj.lives > 0).count() {
+            3.. => Phase::Aimed,
+            2 => Phase::Fan,
+            _ => Phase::Ring,
+        }
+    }
+
+    /// Move the weak points along with the hull
+    fn place(&mut self) {
+        let (x, y) = self.hull.pos;
+
+        for obj in &mut self.weak_points {
+            obj.pos = (x + obj.offset.0, y + obj.offset.1);
+        }
+    }
+
+    /// Move and attack for one simulation step, returning true if the boss
+    /// fired
+    pub fn update(
+        &mut self,
+        ships: &[Object<Ship>],
+        screen: Screen,
+        now: f64,
+        shots: &mut Vec<Object<Shot>>,
+    ) -> bool {
+        let top = screen.top + HOVER;
+
+        // Come into view before attacking
+        if self.hull.pos.1 < top {
+            self.hull.pos.1 = (self.hull.pos.1 + ENTRY_SPEED * STEP).min(top);
+            self.reload = now + Phase::Aimed.cooldown();
+            self.place();
+            return false;
+        }
+
+        let phase = self.phase();
+        let middle = (screen.width as f32 - self.hull.size()) / 2.0;
+        self.sway = (self.sway + phase.sway() * STEP) % TAU;
+        self.hull.pos.0 = middle + SWAY.min(middle.max(0.0)) * self.sway.sin();
+        self.place();
+
+        if self.reload > now {
+            return false;
+        }
+        self.reload = now + phase.cooldown();
+
+        let center = self.hull.center();
+        let toward = |angle: f32| {
+            let (sin, cos) = angle.sin_cos();
+            (SHOT_SPEED * sin, SHOT_SPEED * cos)
+        };
+
+        match phase {
+            Phase::Aimed => {
+                for obj in self.weak_points.iter().filter(|obj| obj.lives > 0) {
+                    let from = obj.center();
+                    let velocity = aim(from, nearest_ship(from, ships), SHOT_SPEED);
+                    shots.push(Object::shot(from, velocity));
+                }
+            }
+            Phase::Fan => {
+                for i in 0..FAN_SHOTS {
+                    let angle = (i as f32 - (FAN_SHOTS - 1) as f32 / 2.0) * FAN_SPREAD;
+                    shots.push(Object::shot(center, toward(angle)));
+                }
+            }
+            Phase::Ring => {
+                for i in 0..RING_SHOTS {
+                    let angle = i as f32 * TAU / RING_SHOTS as f32 + self.sway;
+                    shots.push(Object::shot(center, toward(angle)));
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Draw the hull and the weak points left on it
+    pub fn draw(&self, image: &Texture, canvas: &mut WindowCanvas) {
+        self.hull.draw(image, canvas);
+
+        for obj in self.weak_points.iter().filter(|obj| obj.lives > 0) {
+            obj.draw(image, canvas);
+        }
+    }
+}
+
+impl Object<Hull> {
+    /// Width of the hull on screen, in pixels
+    fn size(&self) -> f32 {
+        self.clip.w as f32 * self.scale
+    }
+}
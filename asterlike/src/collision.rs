@@ -0,0 +1,90 @@
+use std::{
+    fs,
+    io::{self, Error, ErrorKind},
+};
+
+/// Shape checked once the bounding boxes of two objects overlap
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Collider {
+    /// The bounding box itself
+    Box,
+    /// The circle inscribed in the bounding box
+    Circle,
+    /// The pixels of the sprite that are not the color key
+    Pixel,
+}
+
+/// Opaque pixels of a color-keyed sprite sheet, one bit per pixel
+#[derive(Default)]
+pub struct Mask {
+    width: i32,
+    height: i32,
+    bits: Vec<u64>,
+}
+
+impl Mask {
+    /// Read an uncompressed 24 or 32 bit BMP, every pixel of the key color
+    /// being transparent
+    pub fn load(path: &str, key: (u8, u8, u8)) -> io::Result<Self> {
+        let data = fs::read(path)?;
+        let invalid = || Error::new(ErrorKind::InvalidData, "unsupported bitmap");
+        let u32_at = |at: usize| -> io::Result<u32> {
+            let bytes = data.get(at..at + 4).ok_or_else(invalid)?;
+            Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+        };
+
+        if !data.starts_with(b"BM") {
+            return Err(invalid());
+        }
+
+        let offset = u32_at(10)? as usize;
+        let (width, height) = (u32_at(18)? as i32, u32_at(22)? as i32);
+        let depth = data.get(28).copied().ok_or_else(invalid)? as usize;
+
+        // Only plain bitmaps, stored from the bottom row up
+        if !matches!(depth, 24 | 32) || u32_at(30)? != 0 || width <= 0 || height <= 0 {
+            return Err(invalid());
+        }
+
+        let bytes = depth / 8;
+        let pitch = (width as usize * bytes).div_ceil(4) * 4;
+        let mut mask = Mask {
+            width,
+            height,
+            bits: vec![0; (width * height) as usize / 64 + 1],
+        };
+
+        for y in 0..height {
+            let row = offset + (height - 1 - y) as usize * pitch;
+
+            for x in 0..width {
+                let at = row + x as usize * bytes;
+                let Some(&[b, g, r]) = data.get(at..at + 3) else {
+                    return Err(invalid());
+                };
+
+                if (r, g, b) != key {
+                    let i = (y * width + x) as usize;
+                    mask.bits[i / 64] |= 1 << (i % 64);
+                }
+            }
+        }
+
+        Ok(mask)
+    }
+
+    /// Check if the pixel of the sheet at the given coordinates is opaque,
+    /// an empty mask treats every pixel as opaque
+    pub fn is_opaque(&self, (x, y): (i32, i32)) -> bool {
+        if self.bits.is_empty() {
+            return true;
+        }
+
+        if !(0..self.width).contains(&x) || !(0..self.height).contains(&y) {
+            return false;
+        }
+
+        let i = (y * self.width + x) as usize;
+        self.bits[i / 64] & 1 << (i % 64) != 0
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    io::{self, Error, ErrorKind},
+    str::FromStr,
+};
+
+/// File next to the binary holding the config on native builds
+#[cfg(not(target_os = "emscripten"))]
+const CONFIG_FILE: &str = "asterlike.cfg";
+
+/// Window and renderer settings
+#[derive(Clone)]
+pub struct Video {
+    /// Start fullscreen, falling back to a window if it fails
+    pub fullscreen: bool,
+    /// Size of the window when not fullscreen
+    pub size: (u32, u32),
+    /// Wait for the display refresh instead of sleeping between frames
+    pub vsync: bool,
+    /// Render driver asked to SDL, which picks one when unset
+    pub driver: Option<String>,
+}
+
+impl Default for Video {
+    fn default() -> Self {
+        Video {
+            fullscreen: true,
+            size: (500, 800),
+            vsync: false,
+            driver: None,
+        }
+    }
+}
+
+/// Game balance and video settings, read from a config file so they can be
+/// tuned without recompiling
+#[derive(Clone)]
+pub struct Config {
+    /// Speeds in pixels per second, the asteroid one for the first wave
+    pub ship_speed: f32,
+    pub asteroid_speed: f32,
+    pub bullet_speed: f32,
+    /// Factor applied each second to the asteroid speed
+    pub asteroid_acceleration: f32,
+    /// Time between two shots of the ship, in seconds
+    pub bullet_cooldown: f64,
+    /// Time between two shots of the ship with rapid fire, in seconds
+    pub rapid_cooldown: f64,
+    /// Lives of the ship when a game starts, on easy, normal and hard
+    pub lives: [i16; 3],
+    /// Points earned by destroying a small, medium and large asteroid
+    pub asteroid_score: [u32; 3],
+    /// Points earned by destroying an enemy ship
+    pub enemy_score: u32,
+    /// Asteroids spawned per second on average during the first wave
+    pub asteroid_rate: f32,
+    /// Enemy ships spawned per second on average
+    pub enemy_rate: f32,
+    /// Chance, out of 100, that a destroyed asteroid drops a power-up
+    pub drop_chance: i32,
+    /// Score between two boss fights, no boss shows up when 0
+    pub boss_interval: u32,
+    /// Points earned by destroying the last weak point of a boss
+    pub boss_score: u32,
+    pub video: Video,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            ship_speed: 450.0,
+            asteroid_speed: 90.0,
+            bullet_speed: 540.0,
+            asteroid_acceleration: 1.015,
+            bullet_cooldown: 0.15,
+            rapid_cooldown: 0.07,
+            lives: [5, 3, 2],
+            asteroid_score: [1, 2, 3],
+            enemy_score: 5,
+            asteroid_rate: 1.0,
+            enemy_rate: 0.25,
+            drop_chance: 12,
+            boss_interval: 150,
+            boss_score: 50,
+            video: Video::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file next to the binary, or the given one, over the
+    /// defaults; a missing default file leaves the defaults alone
+    #[cfg(not(target_os = "emscripten"))]
+    pub fn load(path: Option<&std::path::Path>) -> io::Result<Self> {
+        let data = match path {
+            Some(path) => std::fs::read_to_string(path)?,
+            None => {
+                match std::fs::read_to_string(std::env::current_exe()?.with_file_name(CONFIG_FILE))
+                {
+                    Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+                    data => data?,
+                }
+            }
+        };
+
+        let mut config = Config::default();
+        config.parse(&data)?;
+        Ok(config)
+    }
+
+    /// The web build has no files, its config is embedded in the binary
+    #[cfg(target_os = "emscripten")]
+    pub fn load(_path: Option<&std::path::Path>) -> io::Result<Self> {
+        let mut config = Config::default();
+        config.parse(include_str!("../asterlike.cfg"))?;
+        Ok(config)
+    }
+
+    /// Read `key = value` lines over the current settings, ignoring blank
+    /// lines and everything after a `#`
+    pub fn parse(&mut self, data: &str) -> io::Result<()> {
+        for (number, line) in data.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            line.split_once('=')
+                .ok_or_else(|| invalid(format!("expected key = value: {line}")))
+                .and_then(|(key, value)| self.set(key.trim(), value.trim()))
+                .map_err(|err| invalid(format!("line {}: {err}", number + 1)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Change one setting from its text, lists are separated by spaces
+    pub fn set(&mut self, key: &str, value: &str) -> io::Result<()> {
+        match key {
+            "ship_speed" => self.ship_speed = parse(key, value)?,
+            "asteroid_speed" => self.asteroid_speed = parse(key, value)?,
+            "bullet_speed" => self.bullet_speed = parse(key, value)?,
+            "asteroid_acceleration" => self.asteroid_acceleration = parse(key, value)?,
+            "bullet_cooldown" => self.bullet_cooldown = parse(key, value)?,
+            "rapid_cooldown" => self.rapid_cooldown = parse(key, value)?,
+            "lives" => self.lives = positive(key, value, parse_list(key, value)?)?,
+            "asteroid_score" => self.asteroid_score = parse_list(key, value)?,
+            "enemy_score" => self.enemy_score = parse(key, value)?,
+            "asteroid_rate" => self.asteroid_rate = parse(key, value)?,
+            "enemy_rate" => self.enemy_rate = parse(key, value)?,
+            "drop_chance" => self.drop_chance = parse(key, value)?,
+            "boss_interval" => self.boss_interval = parse(key, value)?,
+            "boss_score" => self.boss_score = parse(key, value)?,
+            "fullscreen" => self.video.fullscreen = parse(key, value)?,
+            "window_size" => {
+                let [width, height] = positive(key, value, parse_list(key, value)?)?;
+                self.video.size = (width, height);
+            }
+            "vsync" => self.video.vsync = parse(key, value)?,
+            "render_driver" => {
+                self.video.driver = (!value.is_empty()).then(|| value.to_string());
+            }
+            _ => return Err(invalid(format!("unknown setting {key}"))),
+        }
+
+        Ok(())
+    }
+
+    /// Game balance settings as `key = value` lines, read back by `parse`
+    /// so a replay plays with the settings it was recorded with
+    pub fn balance(&self) -> String {
+        let list = |values: &[String]| values.join(" ");
+
+        [
+            format!("ship_speed = {}", self.ship_speed),
+            format!("asteroid_speed = {}", self.asteroid_speed),
+            format!("bullet_speed = {}", self.bullet_speed),
+            format!("asteroid_acceleration = {}", self.asteroid_acceleration),
+            format!("bullet_cooldown = {}", self.bullet_cooldown),
+            format!("rapid_cooldown = {}", self.rapid_cooldown),
+            format!("lives = {}", list(&self.lives.map(|n| n.to_string()))),
+            format!(
+                "asteroid_score = {}",
+                list(&self.asteroid_score.map(|n| n.to_string()))
+            ),
+            format!("enemy_score = {}", self.enemy_score),
+            format!("asteroid_rate = {}", self.asteroid_rate),
+            format!("enemy_rate = {}", self.enemy_rate),
+            format!("drop_chance = {}", self.drop_chance),
+            format!("boss_interval = {}", self.boss_interval),
+            format!("boss_score = {}", self.boss_score),
+        ]
+        .join("\n")
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> io::Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("invalid {key}: {value}")))
+}
+
+/// Reject lists holding a value of 0 or less
+fn positive<T: PartialOrd + Default, const N: usize>(
+    key: &str,
+    value: &str,
+    values: [T; N],
+) -> io::Result<[T; N]> {
+    if values.iter().any(|item| *item <= T::default()) {
+        return Err(invalid(format!("{key} must be above 0: {value}")));
+    }
+
+    Ok(values)
+}
+
+/// Parse exactly `N` values separated by spaces
+fn parse_list<T: FromStr, const N: usize>(key: &str, value: &str) -> io::Result<[T; N]> {
+    let values = value
+        .split_whitespace()
+        .map(|item| parse(key, item))
+        .collect::<io::Result<Vec<T>>>()?;
+
+    values
+        .try_into()
+        .map_err(|_| invalid(format!("{key} needs {N} values: {value}")))
+}
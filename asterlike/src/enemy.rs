@@ -0,0 +1,236 @@
+use crate::{
+    boss::Boss,
+    collision::Collider,
+    global::{GameRng, STEP, Screen},
+    object::{Object, Ship},
+    wave::Wave,
+};
+use sdl3::{
+    rect::Rect,
+    render::{Texture, WindowCanvas},
+};
+
+/// Downward speed of the enemies while they are not diving, in pixels per
+/// second
+const CRUISE_SPEED: f32 = 70.0;
+
+/// Speed of an enemy diving at the ship, in pixels per second
+const DIVE_SPEED: f32 = 320.0;
+
+/// Time an enemy cruises before diving, in seconds
+const DIVE_DELAY: f64 = 1.5;
+
+/// Horizontal reach and angular speed of the sine wave pattern
+const SINE_AMPLITUDE: f32 = 120.0;
+const SINE_FREQUENCY: f32 = 2.0;
+
+/// Speed of the enemy bullets, in pixels per second
+pub(crate) const SHOT_SPEED: f32 = 240.0;
+
+/// Shortest time between two shots of an enemy, in seconds
+const SHOT_COOLDOWN: f64 = 1.5;
+
+/// How an enemy moves down the screen
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Pattern {
+    /// Sways from side to side around the column it entered from
+    Sine,
+    /// Cruises for a while, then dives at where the ship was
+    Dive,
+}
+
+pub struct Enemy {
+    pub(crate) lives: i16,
+    pattern: Pattern,
+    /// Time spent on screen, in seconds
+    age: f64,
+    /// Horizontal center of the sine wave
+    origin: f32,
+    /// Movement per second once diving
+    velocity: Option<(f32, f32)>,
+    /// Time at which the enemy can fire again
+    reload: f64,
+}
+
+/// A bullet fired by an enemy
+pub struct Shot {
+    pub(crate) lives: i16,
+    velocity: (f32, f32),
+}
+
+impl Object<Shot> {
+    /// Create a bullet centered on the given point
+    pub(crate) fn shot(from: (f32, f32), velocity: (f32, f32)) -> Self {
+        let mut shot = Object::new(Shot { lives: 1, velocity }, 0, Rect::new(416, 0, 8, 8), 1.0);
+
+        shot.center_on(from);
+        shot.collider = Collider::Circle;
+        shot
+    }
+}
+
+#[derive(Default)]
+pub struct Enemies {
+    pub(crate) objs: Vec<Object<Enemy>>,
+    pub(crate) shots: Vec<Object<Shot>>,
+    /// Boss fought at milestone scores, in place of the rest of the wave
+    pub(crate) boss: Option<Boss>,
+}
+
+impl Enemies {
+    pub fn new() -> Self {
+        Enemies {
+            objs: Vec::new(),
+            shots: Vec::new(),
+            boss: None,
+        }
+    }
+
+    /// Spawn, move and fire the enemies and the boss for one simulation
+    /// step, returning true if any of them fired
+    pub fn update(
+        &mut self,
+        wave: &mut Wave,
+        ships: &[Object<Ship>],
+        screen: Screen,
+        now: f64,
+        rng: &mut GameRng,
+    ) -> bool {
+        let odds = (wave.enemy_rate * STEP * 1000.0) as i32;
+
+        if wave.enemies > 0 && rng.rand() % 1000 < odds {
+            wave.enemies -= 1;
+            self.spawn(screen, now, rng);
+        }
+
+        let mut fired = false;
+
+        for obj in &mut self.objs {
+            obj.age += f64::from(STEP);
+            let target = nearest_ship(obj.center(), ships);
+
+            match (obj.pattern, obj.velocity) {
+                (Pattern::Sine, _) => {
+                    obj.pos.0 =
+                        obj.origin + SINE_AMPLITUDE * (obj.age as f32 * SINE_FREQUENCY).sin();
+                    obj.advance((0.0, CRUISE_SPEED));
+                }
+                (Pattern::Dive, Some(velocity)) => obj.advance(velocity),
+                (Pattern::Dive, None) => {
+                    if obj.age >= DIVE_DELAY {
+                        obj.velocity = Some(aim(obj.center(), target, DIVE_SPEED));
+                    }
+                    obj.advance((0.0, CRUISE_SPEED));
+                }
+            }
+
+            // Only fire once fully on screen and while not diving
+            if obj.reload <= now && obj.pos.1 >= 0.0 && obj.velocity.is_none() {
+                let from = obj.center();
+                let velocity = aim(from, target, SHOT_SPEED);
+                self.shots
+                    .push(Object::shot((from.0, from.1 + 4.0), velocity));
+
+                obj.reload = now + SHOT_COOLDOWN + f64::from(rng.rand() % 100) / 100.0;
+                fired = true;
+            }
+        }
+
+        if let Some(boss) = &mut self.boss {
+            fired |= boss.update(ships, screen, now, &mut self.shots);
+        }
+
+        self.objs.retain(|obj| {
+            obj.lives > 0
+                && obj.pos.1 <= screen.height as f32
+                && obj.pos.0 + obj.clip.w as f32 >= 0.0
+                && obj.pos.0 <= screen.width as f32
+        });
+
+        self.shots.retain_mut(|shot| {
+            let velocity = shot.velocity;
+            shot.advance(velocity);
+
+            shot.lives > 0
+                && (0.0..=screen.width as f32).contains(&shot.pos.0)
+                && (-shot.clip.h as f32..=screen.height as f32).contains(&shot.pos.1)
+        });
+
+        fired
+    }
+
+    fn spawn(&mut self, screen: Screen, now: f64, rng: &mut GameRng) {
+        let clip = Rect::new(384, 0, 32, 32);
+        let pattern = if rng.rand() % 2 == 0 {
+            Pattern::Sine
+        } else {
+            Pattern::Dive
+        };
+
+        // Keep the whole sine wave inside the screen
+        let margin = match pattern {
+            Pattern::Sine => SINE_AMPLITUDE as i32,
+            Pattern::Dive => 0,
+        };
+        let room = (screen.width - clip.w - 2 * margin).max(1);
+        let x = (margin + rng.rand() % room) as f32;
+
+        let mut obj = Object::new(
+            Enemy {
+                lives: 2,
+                pattern,
+                age: 0.0,
+                origin: x,
+                velocity: None,
+                reload: now + SHOT_COOLDOWN,
+            },
+            0,
+            clip,
+            1.0,
+        );
+
+        obj.pos = (x, -clip.h as f32);
+        obj.collider = Collider::Pixel;
+        self.objs.push(obj);
+    }
+
+    /// Check if every enemy and the boss are gone
+    pub fn is_empty(&self) -> bool {
+        self.objs.is_empty() && self.boss.is_none()
+    }
+
+    pub fn draw(&self, image: &Texture, canvas: &mut WindowCanvas) {
+        for obj in &self.objs {
+            obj.draw(image, canvas);
+        }
+
+        if let Some(boss) = &self.boss {
+            boss.draw(image, canvas);
+        }
+
+        for shot in &self.shots {
+            shot.draw(image, canvas);
+        }
+    }
+}
+
+/// Center of the playing ship closest to a point, or the point right below
+/// it once every ship is out
+pub(crate) fn nearest_ship(from: (f32, f32), ships: &[Object<Ship>]) -> (f32, f32) {
+    let distance = |to: &(f32, f32)| (to.0 - from.0).hypot(to.1 - from.1);
+
+    ships
+        .iter()
+        .filter(|ship| ship.is_playing())
+        .map(|ship| ship.center())
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+        .unwrap_or((from.0, from.1 + 1.0))
+}
+
+/// Velocity of the given speed going from one point towards another
+pub(crate) fn aim(from: (f32, f32), to: (f32, f32), speed: f32) -> (f32, f32) {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = dx.hypot(dy).max(1.0);
+
+    (dx / length * speed, dy / length * speed)
+}
@@ -0,0 +1,126 @@
+use crate::assets::{Assets, TextureId};
+use sdl3::{
+    rect::Rect,
+    render::{FRect, WindowCanvas},
+};
+use std::collections::HashMap;
+
+/// Glyph drawn for characters missing from a font
+const FALLBACK: char = '?';
+
+/// Horizontal alignment of a text relative to its position
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Where each character of a bitmap font is in its texture
+pub struct GlyphMap {
+    glyphs: HashMap<char, Rect>,
+    /// Size of the area drawn for each glyph
+    cell: (u32, u32),
+    /// Distance from one glyph to the next
+    advance: u32,
+}
+
+impl GlyphMap {
+    /// Glyphs laid out in rows of the given number of columns, in the
+    /// order of `chars`
+    pub fn grid(chars: &str, columns: u32, cell: (u32, u32), advance: u32) -> Self {
+        let glyphs = chars
+            .chars()
+            .enumerate()
+            .map(|(i, chr)| {
+                let (column, row) = (i as u32 % columns, i as u32 / columns);
+                let clip = Rect::new(
+                    (column * cell.0) as i32,
+                    (row * cell.1) as i32,
+                    cell.0,
+                    cell.1,
+                );
+                (chr, clip)
+            })
+            .collect();
+
+        GlyphMap {
+            glyphs,
+            cell,
+            advance,
+        }
+    }
+
+    /// The printable ASCII characters, from space to `~`
+    pub fn ascii(columns: u32, cell: (u32, u32), advance: u32) -> Self {
+        let chars: String = (b' '..=b'~').map(char::from).collect();
+        GlyphMap::grid(&chars, columns, cell, advance)
+    }
+}
+
+/// Text renderer drawing the glyphs of a texture
+pub struct Font {
+    texture: TextureId,
+    map: GlyphMap,
+    scale: f32,
+}
+
+impl Font {
+    pub fn new(texture: TextureId, map: GlyphMap, scale: f32) -> Self {
+        Font {
+            texture,
+            map,
+            scale,
+        }
+    }
+
+    /// Height of a line of text, in pixels
+    pub fn height(&self) -> i32 {
+        (self.map.cell.1 as f32 * self.scale) as i32
+    }
+
+    /// Width taken by a text, in pixels
+    pub fn measure(&self, text: &str) -> i32 {
+        (text.chars().count() as f32 * self.map.advance as f32 * self.scale) as i32
+    }
+
+    /// Draw a text with its top at `y`, aligned to `x`
+    pub fn draw(
+        &self,
+        text: &str,
+        (x, y): (i32, i32),
+        align: Align,
+        assets: &Assets,
+        canvas: &mut WindowCanvas,
+    ) {
+        let left = match align {
+            Align::Left => x,
+            Align::Center => x - self.measure(text) / 2,
+            Align::Right => x - self.measure(text),
+        };
+        let (w, h) = (
+            self.map.cell.0 as f32 * self.scale,
+            self.map.cell.1 as f32 * self.scale,
+        );
+        let advance = self.map.advance as f32 * self.scale;
+        let image = assets.get(self.texture);
+
+        for (i, chr) in text.chars().enumerate() {
+            let Some(&clip) = self
+                .map
+                .glyphs
+                .get(&chr)
+                .or_else(|| self.map.glyphs.get(&FALLBACK))
+            else {
+                continue;
+            };
+
+            let src: Option<FRect> = clip.into();
+            let dst = FRect::new(left as f32 + advance * i as f32, y as f32, w, h);
+
+            canvas
+                .copy(image, src, Some(dst))
+                .expect("Could not blit texture to render target!");
+        }
+    }
+}
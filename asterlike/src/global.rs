@@ -1,44 +1,72 @@
-use crate::object::{Asteroids, Object, Ship, Size, Text};
-use rand::RngExt;
+use crate::{
+    assets::{Assets, TextureId},
+    audio::Sound,
+    boss::Boss,
+    collision::Mask,
+    config::{Config, Video},
+    enemy::Enemies,
+    font::Align,
+    input::Input,
+    object::{Asteroids, Object, Ship, Size, total_score},
+    particle::Effect,
+    powerup::{DURATION, PowerUp, PowerUps},
+    score::INITIALS,
+    starfield::Starfield,
+    wave::{WAVE_DELAY, Wave},
+};
+use rand::{RngExt, SeedableRng, rngs::StdRng};
 use sdl3::{
     pixels::Color,
     rect::Rect,
-    render::{Texture, WindowCanvas},
+    render::{FRect, WindowCanvas},
     video::Window,
 };
 
-pub(crate) fn rand() -> i32 {
-    rand::rng().random_range(0..=i32::MAX)
+/// Simulation steps per second
+pub const STEPS_PER_SECOND: u32 = 60;
+
+/// Duration of a simulation step, in seconds
+pub const STEP: f32 = 1.0 / STEPS_PER_SECOND as f32;
+
+/// Random numbers of a game, seeded so it can be replayed exactly
+pub(crate) struct GameRng(StdRng);
+
+impl GameRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        GameRng(StdRng::seed_from_u64(seed))
+    }
+
+    pub(crate) fn rand(&mut self) -> i32 {
+        self.0.random_range(0..=i32::MAX)
+    }
 }
 
-pub fn init() -> (sdl3::Sdl, Window) {
+/// Open the window with the given settings, the renderer created from it
+/// follows the render driver and vsync hints
+pub fn init(video: &Video) -> (sdl3::Sdl, Window) {
     let sdl = sdl3::init().expect("Unable to initialize SDL!");
 
-    // In VM / poor-driver environments, SDL's default renderer selection can end up
-    // going through EGL + Zink (Vulkan) and fail at runtime. Prefer software
-    // rendering unless the user explicitly overrides it.
-    // NOTE: In Emscripten/Web builds, forcing the software renderer can break
-    // rendering because the backend is different (typically WebGL).
-    #[cfg(not(target_os = "emscripten"))]
-    {
-        if std::env::var_os("SDL_RENDER_DRIVER").is_none() {
-            sdl3::hint::set("SDL_RENDER_DRIVER", "software");
-        }
+    // The SDL_RENDER_DRIVER environment variable still takes precedence
+    if let Some(driver) = &video.driver {
+        sdl3::hint::set("SDL_RENDER_DRIVER", driver);
     }
+    sdl3::hint::set("SDL_RENDER_VSYNC", if video.vsync { "1" } else { "0" });
 
     sdl3::hint::set("SDL_RENDER_SCALE_QUALITY", "1");
     sdl3::hint::set("SDL_VIDEO_X11_NET_WM_BYPASS_COMPOSITOR", "0");
 
-    let video = sdl.video().expect("Could not acquire video context!");
-
-    let build_win = video
-        .window("Asterlike", 500, 800)
+    let (width, height) = video.size;
+    let build_win = sdl
+        .video()
+        .expect("Could not acquire video context!")
+        .window("Asterlike", width, height)
         .position_centered()
         .build()
         .expect("Could not create SDL window!");
     (sdl, build_win)
 }
 
+/// Speeds in pixels per second
 #[derive(Copy, Clone)]
 pub struct Speed {
     pub ship: f32,
@@ -57,21 +85,101 @@ pub struct Screen {
 }
 
 /// Game enumerated states
-#[derive(Eq, PartialEq)]
-#[allow(dead_code)]
+#[derive(Copy, Clone, Eq, PartialEq)]
 pub enum State {
     Default,
-    CutScene,
     Menu,
+    /// Interstitial shown before each wave
     Wave,
     Pause,
+    /// Final score, with the initials entry for a new high score
+    GameOver,
+}
+
+/// Difficulty levels selectable from the options menu
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+            .into_iter()
+            .find(|difficulty| difficulty.name() == name)
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Hard,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Easy,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+        }
+    }
+
+    /// Factor applied to the initial asteroid speed
+    pub fn asteroid_speed(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+        }
+    }
+}
+
+/// Player settings, kept between games
+#[derive(Copy, Clone)]
+pub struct Options {
+    /// Volume from 0 to `Options::MAX_VOLUME`
+    pub volume: u8,
+    pub difficulty: Difficulty,
+    pub fullscreen: bool,
+    /// Ships playing together, from 1 to `Options::MAX_PLAYERS`
+    pub players: usize,
 }
 
+impl Options {
+    pub const MAX_VOLUME: u8 = 10;
+    pub const MAX_PLAYERS: usize = 2;
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            volume: 7,
+            difficulty: Difficulty::Normal,
+            fullscreen: true,
+            players: 1,
+        }
+    }
+}
+
+/// Simulated time since the game started, in seconds
 #[derive(Copy, Clone, Default)]
 pub(crate) struct Timer {
-    pub(crate) global: u64,
-    pub(crate) bullet: u64,
+    pub(crate) global: f64,
+    /// Whole seconds played, shown in the HUD
     pub(crate) game: u64,
+    /// Time at which the current wave begins
+    pub(crate) wave: f64,
 }
 
 pub(crate) struct Global {
@@ -81,142 +189,497 @@ pub(crate) struct Global {
     pub(crate) exit: bool,
     pub(crate) state: State,
     pub(crate) timer: Timer,
-    pub(crate) score: u32,
+    pub(crate) options: Options,
+    pub(crate) wave: Wave,
+    /// Sounds triggered during the current frame
+    pub(crate) sounds: Vec<Sound>,
+    /// Visual effects triggered during the current step
+    pub(crate) effects: Vec<Effect>,
+    pub(crate) rng: GameRng,
+    /// Opaque pixels of the sprite sheet, for pixel colliders
+    pub(crate) mask: Mask,
+    pub(crate) stars: Starfield,
+    pub(crate) config: Config,
+    /// Score at which the next boss shows up
+    pub(crate) boss_at: u32,
+    /// Bosses defeated, each one makes the following waves harder
+    pub(crate) bosses: u32,
 }
 
 impl Global {
-    pub fn new((width, height): (u32, u32)) -> Self {
+    pub fn new((width, height): (u32, u32), config: Config) -> Self {
         let frames_per_second = 60.0;
-        let tick_ratio = 60.0 / frames_per_second;
+        let screen = Screen {
+            width: width as _,
+            height: height as _,
+            top: height as f32 * 0.06,
+            bottom: 5.0,
+            left: 5.0,
+            right: 5.0,
+        };
 
         // Constant Logic / initialize
         Global {
             speed: Speed {
-                ship: 7.5 * tick_ratio,
-                asteroid: 1.5 * tick_ratio,
-                bullet: 9.0 * tick_ratio,
-            },
-            screen: Screen {
-                width: width as _,
-                height: height as _,
-                top: height as f32 * 0.06,
-                bottom: 5.0,
-                left: 5.0,
-                right: 5.0,
+                ship: config.ship_speed,
+                asteroid: config.asteroid_speed,
+                bullet: config.bullet_speed,
             },
+            screen,
 
-            // Make this an option
+            // Rendering rate, the simulation always runs at `STEPS_PER_SECOND`
             frames_per_second,
 
             /* Initialize Keystates */
             // Global->keystates = SDL_GetKeyboardState(NULL);
             exit: false,
-            state: State::Default,
+            state: State::Menu,
             timer: Timer::default(),
 
-            // Global Score
-            score: 0,
+            options: Options::default(),
+            wave: Wave::new(1, &config),
+            sounds: Vec::new(),
+            effects: Vec::new(),
+            rng: GameRng::new(0),
+            mask: Mask::default(),
+            stars: Starfield::new(screen),
+            boss_at: config.boss_interval,
+            bosses: 0,
+            config,
         }
     }
 
+    /// Prepare a new game from a random seed, keeping the screen size, the
+    /// player options, the sprite sheet mask and the config
+    pub(crate) fn reset(&mut self, seed: u64) {
+        let (screen, options) = (self.screen, self.options);
+        let mask = std::mem::take(&mut self.mask);
+        let config = std::mem::take(&mut self.config);
+
+        *self = Global::new((screen.width as u32, screen.height as u32), config);
+        self.screen = screen;
+        self.options = options;
+        self.mask = mask;
+        self.rng = GameRng::new(seed);
+    }
+
+    /// Prepare a new game from the given seed, replacing the ships, the
+    /// asteroids and the enemies of the previous one
+    pub(crate) fn start_game(
+        &mut self,
+        seed: u64,
+        ships: &mut Vec<Object<Ship>>,
+        asteroids: &mut Asteroids,
+        enemies: &mut Enemies,
+        power_ups: &mut PowerUps,
+    ) {
+        self.reset(seed);
+        self.start_wave(1);
+        *asteroids = Asteroids::new();
+        *enemies = Enemies::new();
+        *power_ups = PowerUps::new();
+
+        let lives = self.config.lives[self.options.difficulty as usize];
+        let players = self.options.players;
+        *ships = (0..players)
+            .map(|player| Object::spawn(lives, self.screen, player, players))
+            .collect();
+    }
+
+    /// Advance the simulated time by one step
+    pub(crate) fn advance(&mut self) {
+        self.timer.global += f64::from(STEP);
+        self.timer.game = self.timer.global as u64;
+    }
+
+    /// Show the interstitial of the given wave, then let it spawn asteroids
+    pub(crate) fn start_wave(&mut self, number: u32) {
+        self.wave = Wave::new(number, &self.config).harden(self.bosses);
+        self.speed.asteroid =
+            self.config.asteroid_speed * self.options.difficulty.asteroid_speed() * self.wave.speed;
+        self.timer.wave = self.timer.global + WAVE_DELAY;
+        self.state = State::Wave;
+    }
+
+    /// Check if the current wave is over and the next one should start
+    pub(crate) fn is_wave_cleared(&self, asteroids: &Asteroids, enemies: &Enemies) -> bool {
+        self.wave.is_spawned()
+            && self.wave.enemies == 0
+            && asteroids.objs.is_empty()
+            && enemies.is_empty()
+    }
+
     pub(crate) fn update_screen_size(&mut self, (width, height): (u32, u32)) {
         // Keep the existing margins, but sync to the real render output size.
         self.screen.width = width as i32;
         self.screen.height = height as i32;
         self.screen.top = height as f32 * 0.06;
+        self.stars = Starfield::new(self.screen);
     }
 
-    pub(crate) fn collision<'tex>(
+    /// Run one simulation step of a game with the input of each player,
+    /// without touching the window so it can also run headless
+    pub(crate) fn step(
         &mut self,
-        ship: &mut Object<'tex, Ship<'tex>>,
-        asteroids: &mut Asteroids<'tex>,
+        ships: &mut [Object<Ship>],
+        asteroids: &mut Asteroids,
+        enemies: &mut Enemies,
+        power_ups: &mut PowerUps,
+        inputs: &[Input],
     ) {
+        if !matches!(self.state, State::Default | State::Wave) {
+            return;
+        }
+
+        self.advance();
+
+        for (ship, input) in ships.iter_mut().zip(inputs) {
+            let items = (self.screen, self.speed, &self.timer, input, &self.config);
+            if ship.is_playing() && ship.update(items) {
+                self.sounds.push(Sound::Shoot);
+            }
+        }
+
+        for (player, power) in power_ups.update(ships, self.screen, &self.mask) {
+            ships[player].power_up(power, self.timer.global);
+            self.sounds.push(Sound::PowerUp);
+        }
+
+        if self.state == State::Wave {
+            // The ships can move around while the next wave is announced
+            if self.timer.global >= self.timer.wave {
+                self.state = State::Default;
+            }
+            return;
+        }
+
+        let acceleration = self.config.asteroid_acceleration;
+        asteroids.update(
+            &mut self.wave,
+            self.screen,
+            &mut self.speed,
+            acceleration,
+            &mut self.rng,
+        );
+        let now = self.timer.global;
+        if enemies.update(&mut self.wave, ships, self.screen, now, &mut self.rng) {
+            self.sounds.push(Sound::Shoot);
+        }
+        self.collision(ships, asteroids, enemies, power_ups);
+
+        // A boss takes the place of the rest of the wave at every milestone
+        let (interval, score) = (self.config.boss_interval, total_score(ships));
+        if interval > 0 && score >= self.boss_at && enemies.boss.is_none() {
+            self.boss_at = (score / interval + 1) * interval;
+            self.wave.budget = 0;
+            self.wave.enemies = 0;
+            enemies.boss = Some(Boss::new(self.screen, self.bosses, now));
+        }
+
+        if self.is_wave_cleared(asteroids, enemies) {
+            self.start_wave(self.wave.number + 1);
+        }
+
+        // Show the final score once every player is out of lives
+        if !ships.iter().any(|ship| ship.is_playing()) {
+            self.state = State::GameOver;
+            self.sounds.push(Sound::GameOver);
+        }
+    }
+
+    /// Hit the ship, the shield absorbs the hit instead of a life
+    fn damage(&mut self, ship: &mut Object<Ship>) {
+        if ship.shield {
+            ship.shield = false;
+        } else {
+            ship.lives -= 1;
+        }
+
+        // A ship out of lives leaves the game with its bullets
+        if !ship.is_playing() {
+            ship.bullets.clear();
+        }
+        self.sounds.push(Sound::Damage);
+        self.effects.push(Effect::Shake);
+        self.effects.push(Effect::Blast {
+            center: ship.center(),
+            count: 6,
+        });
+    }
+
+    /// Throw debris out of a destroyed object, more for bigger ones
+    fn blast<T>(&mut self, obj: &Object<T>) {
+        self.effects.push(Effect::Blast {
+            center: obj.center(),
+            count: (obj.clip.w / 4) as usize,
+        });
+    }
+
+    pub(crate) fn collision(
+        &mut self,
+        ships: &mut [Object<Ship>],
+        asteroids: &mut Asteroids,
+        enemies: &mut Enemies,
+        power_ups: &mut PowerUps,
+    ) {
+        let mut fragments = Vec::new();
+
         for asteroid in asteroids.objs.iter_mut() {
-            if asteroid.is_collision(ship) {
-                ship.lives -= 1;
-                asteroid.lives = 0;
+            for ship in ships.iter_mut().filter(|ship| ship.is_playing()) {
+                if asteroid.lives > 0 && asteroid.is_collision(ship, &self.mask) {
+                    asteroid.lives = 0;
+                    self.damage(ship);
+                    self.blast(asteroid);
+                }
+
+                let Ship { bullets, score, .. } = &mut **ship;
+
+                for bullet in bullets.iter_mut() {
+                    // Destroyed asteroids must not split or score twice
+                    if asteroid.lives > 0 && asteroid.is_collision(bullet, &self.mask) {
+                        asteroid.lives -= 1;
+                        bullet.lives = 0;
+                        self.sounds.push(Sound::Hit);
+
+                        if asteroid.lives <= 0 {
+                            let [small, medium, large] = self.config.asteroid_score;
+                            *score += match asteroid.kind {
+                                Size::Small => small,
+                                Size::Medium => medium,
+                                Size::Large => large,
+                                _ => unreachable!(),
+                            };
+                            fragments.extend(asteroid.fragments(&mut self.rng));
+                            self.blast(asteroid);
+
+                            let chance = self.config.drop_chance;
+                            if let Some(power) = PowerUp::roll(chance, &mut self.rng) {
+                                power_ups.spawn(power, asteroid.center());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        asteroids.objs.extend(fragments);
+
+        for enemy in enemies.objs.iter_mut() {
+            for ship in ships.iter_mut().filter(|ship| ship.is_playing()) {
+                // Ramming a ship destroys the enemy without scoring
+                if enemy.lives > 0 && enemy.is_collision(ship, &self.mask) {
+                    enemy.lives = 0;
+                    self.damage(ship);
+                    self.blast(enemy);
+                }
+
+                let Ship { bullets, score, .. } = &mut **ship;
+
+                for bullet in bullets.iter_mut() {
+                    if enemy.lives > 0 && bullet.lives > 0 && enemy.is_collision(bullet, &self.mask)
+                    {
+                        enemy.lives -= 1;
+                        bullet.lives = 0;
+                        self.sounds.push(Sound::Hit);
+
+                        if enemy.lives <= 0 {
+                            *score += self.config.enemy_score;
+                            self.blast(enemy);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(boss) = &mut enemies.boss {
+            self.boss_collision(ships, boss);
+
+            if boss.is_defeated() {
+                self.bosses += 1;
+                self.blast(&boss.hull);
+                self.effects.push(Effect::Shake);
+                enemies.boss = None;
             }
+        }
 
-            for bullet in ship.bullets.iter_mut() {
-                if asteroid.is_collision(bullet) {
-                    asteroid.lives -= 1;
+        for shot in enemies.shots.iter_mut() {
+            for ship in ships.iter_mut().filter(|ship| ship.is_playing()) {
+                if shot.lives > 0 && shot.is_collision(ship, &self.mask) {
+                    shot.lives = 0;
+                    self.damage(ship);
+                }
+            }
+        }
+    }
+
+    /// Hit the weak points of the boss with the bullets of the ships, the
+    /// hull stops bullets and pushes back the ships running into it
+    fn boss_collision(&mut self, ships: &mut [Object<Ship>], boss: &mut Boss) {
+        for ship in ships.iter_mut().filter(|ship| ship.is_playing()) {
+            if boss.hull.is_collision(ship, &self.mask) {
+                self.damage(ship);
+                // Push the ship below the hull without leaving the screen
+                let (_, bottom) = boss.hull.corner();
+                let lowest = self.screen.height as f32 - self.screen.bottom - ship.clip.h as f32;
+                ship.pos.1 = (bottom + 1.0).min(lowest);
+            }
+
+            let Ship { bullets, score, .. } = &mut **ship;
+
+            for bullet in bullets.iter_mut().filter(|bullet| bullet.lives > 0) {
+                let point = boss
+                    .weak_points
+                    .iter_mut()
+                    .find(|point| point.lives > 0 && point.is_collision(bullet, &self.mask));
+
+                if let Some(point) = point {
+                    point.lives -= 1;
                     bullet.lives = 0;
+                    self.sounds.push(Sound::Hit);
 
-                    if asteroid.lives <= 0 {
-                        self.score += match asteroid.kind {
-                            Size::Small => 1,
-                            Size::Medium => 2,
-                            Size::Large => 3,
-                            _ => unreachable!(),
-                        };
+                    if point.lives <= 0 {
+                        *score += self.config.enemy_score;
+                        self.blast(point);
+
+                        // The last weak point is worth the whole boss
+                        if boss.is_defeated() {
+                            *score += self.config.boss_score;
+                            return;
+                        }
                     }
+                } else if boss.hull.is_collision(bullet, &self.mask) {
+                    bullet.lives = 0;
                 }
             }
         }
     }
 
-    /// Display the user's heads up display
-    pub(crate) fn hud(&mut self, ship: &Ship<'_>, font: &Texture, canvas: &mut WindowCanvas) {
-        let Global { screen, score, .. } = self;
+    /// Display the user's heads up display, split between the players
+    /// when there are two of them
+    pub(crate) fn hud(
+        &mut self,
+        ships: &[Object<Ship>],
+        boss: Option<&Boss>,
+        assets: &Assets,
+        canvas: &mut WindowCanvas,
+    ) {
+        let Global { screen, .. } = self;
+        let font = assets.font();
 
         /* Set the HUD bar */
         let bar = Rect::new(
             0,
             0,
             screen.width as u32,
-            (Size::Large as usize as f32 / 2.0 + screen.top) as u32,
+            (font.height() as f32 / 2.0 + screen.top) as u32,
         );
-        let mut data = score.to_string();
 
         canvas.set_draw_color(Color::from((0, 51, 102, 255)));
         canvas.fill_rect(bar).unwrap();
         canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
-        let mut tmp = Text::new(font, "Score", Size::Large, 1.0);
 
-        // Display score number
-        let mut previous = {
-            tmp.position((0, 0), canvas);
+        let time = self.timer.game.to_string();
 
-            let obj = &tmp.chucks()[0];
-            let len = (tmp.chucks().len() + 2 - data.len()) as f32;
-            obj.pos.0 as f32 + (len * obj.clip.w as f32 * 0.5 * obj.scale)
-        } as i32;
+        if let [ship] = ships {
+            // Display score, lives and wave from the left, two spaces apart
+            let gap = font.measure("  ");
+            let mut x = 0;
 
-        tmp = Text::new(font, &data, Size::Large, 1.0);
-        tmp.position((previous, 0), canvas);
+            for data in [
+                format!("Score {}", ship.score),
+                format!("Lives {}", ship.lives),
+                format!("Wave {}", self.wave.number),
+            ] {
+                font.draw(&data, (x, 0), Align::Left, assets, canvas);
+                x += font.measure(&data) + gap;
+            }
 
-        // Display lives text
-        let mut obj = &tmp.chucks()[0];
-        previous += ((tmp.chucks().len() + 3) as f32 * obj.clip.w as f32 * 0.5 * obj.scale) as i32;
+            // Display Timer
+            font.draw(&time, (self.screen.width, 0), Align::Right, assets, canvas);
+        } else {
+            // Each player on its own side, the wave and timer in the middle
+            for (player, ship) in ships.iter().enumerate() {
+                let data = format!("{}P {} x{}", player + 1, ship.score, ship.lives.max(0));
+                let (x, align) = match player {
+                    0 => (0, Align::Left),
+                    _ => (self.screen.width, Align::Right),
+                };
+                font.draw(&data, (x, 0), align, assets, canvas);
+            }
 
-        tmp = Text::new(font, "Lives", Size::Large, 1.0);
-        tmp.position((previous, 0), canvas);
+            let data = format!("W{} {time}", self.wave.number);
+            font.draw(
+                &data,
+                (self.screen.width / 2, 0),
+                Align::Center,
+                assets,
+                canvas,
+            );
+        }
 
-        // Display lives number
-        data = ship.lives.to_string();
-        obj = &tmp.chucks()[0];
+        // Display the timed power-ups under the side of their player, with
+        // a bar showing the time left
+        let y = bar.h + 4;
 
-        previous = {
-            let len = (tmp.chucks().len() + 3 - data.len()) as f32;
-            obj.pos.0 as f32 + (len * obj.clip.w as f32 * 0.5 * obj.scale)
-        } as i32;
+        for (player, ship) in ships.iter().enumerate() {
+            let mut x =
+                self.screen.left as i32 + self.screen.width * player as i32 / ships.len() as i32;
 
-        Text::new(font, &data, Size::Large, 1.0).position((previous, 0), canvas);
+            for (power, until) in [(PowerUp::Spread, ship.spread), (PowerUp::Rapid, ship.rapid)] {
+                let left = until - self.timer.global;
+                if left <= 0.0 || !ship.is_playing() {
+                    continue;
+                }
 
-        // Display Timer
-        tmp = Text::new(font, &self.timer.game.to_string()[..], Size::Large, 1.0);
-        let (w, len) = (self.screen.width as usize, tmp.chucks().len());
-        tmp.position(
-            ((w - (len * tmp.chucks()[0].clip.w as usize)) as i32, 0),
-            canvas,
-        );
+                let clip = power.clip();
+                let icon = Rect::new(x, y, clip.w as u32, clip.h as u32);
+                let (src, dst): (Option<FRect>, Option<FRect>) = (clip.into(), icon.into());
+                canvas
+                    .copy(assets.get(TextureId::SpriteSheet), src, dst)
+                    .expect("Could not blit texture to render target!");
+
+                let width = (clip.w as f64 * 2.0 * left / DURATION) as u32;
+                canvas.set_draw_color(Color::from((0xff, 0xff, 0xff, 255)));
+                canvas
+                    .fill_rect(Rect::new(x + clip.w + 4, y + clip.h / 2 - 2, width, 4))
+                    .unwrap();
+                canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
+
+                x += clip.w * 4;
+            }
+        }
+
+        // Display the health of the boss across the middle of the screen,
+        // below the power-ups
+        if let Some(boss) = boss {
+            let frame = Rect::new(
+                self.screen.width / 4,
+                y + 20,
+                self.screen.width as u32 / 2,
+                8,
+            );
+            let mut health = frame;
+            health.set_width((frame.w as f32 * boss.health()) as u32);
+
+            canvas.set_draw_color(Color::from((0xcc, 0x22, 0x22, 255)));
+            canvas.fill_rect(health).unwrap();
+            canvas.set_draw_color(Color::from((0xff, 0xff, 0xff, 255)));
+            canvas.draw_rect(frame).unwrap();
+            canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
+        }
     }
 
     #[cfg(target_os = "emscripten")]
     pub(crate) fn delay(&self, _frame_start_ms: u64) {}
 
+    /// Sleep for the rest of the frame, unless vsync already waits for the
+    /// display
     #[cfg(not(target_os = "emscripten"))]
     pub(crate) fn delay(&self, frame_start_ms: u64) {
+        if self.config.video.vsync {
+            return;
+        }
+
         let frame_ms = 1_000.0 / self.frames_per_second;
         let elapsed_ms = sdl3::timer::ticks().saturating_sub(frame_start_ms) as f32;
         if elapsed_ms < frame_ms {
@@ -231,30 +694,170 @@ impl Global {
 // static STATIC: Type = init;
 
 /// Display text in middle of the screen
-pub fn text_middle<'tex>(mut text: Text<'tex>, screen: Screen, canvas: &mut WindowCanvas) {
-    let chucks = text.chucks();
-    let clip = chucks[0].clip();
-    let len = chucks.len() as f32 * clip.w as f32 * 0.5 * chucks[0].scale();
-
-    text.position(
-        (
-            (((screen.width as f32 - screen.right) / 2.0 + screen.left) - len / 2.0) as i32,
-            ((((screen.height as f32 - screen.bottom) / 2.0) + screen.top) - clip.h as f32 / 2.0)
-                as i32,
-        ),
-        canvas,
-    );
+pub fn text_middle(text: &str, assets: &Assets, screen: Screen, canvas: &mut WindowCanvas) {
+    let height = assets.font().height() as f32;
+    let y = ((screen.height as f32 - screen.bottom) / 2.0) + screen.top - height / 2.0;
+
+    text_center(text, y as i32, assets, screen, canvas);
 }
 
-/// Display the game over message
-pub fn game_over(font: &Texture, screen: Screen, canvas: &mut WindowCanvas) {
-    canvas.clear();
-    text_middle(
-        Text::new(font, "Game Over", Size::Large, 1.0),
-        screen,
-        canvas,
-    );
-    canvas.present();
-    #[cfg(not(target_os = "emscripten"))]
-    std::thread::sleep(std::time::Duration::new(0, 1_000_000_000u32 * 2));
+/// Display text horizontally centered at the given height
+pub fn text_center(text: &str, y: i32, assets: &Assets, screen: Screen, canvas: &mut WindowCanvas) {
+    let x = (screen.width as f32 - screen.right) / 2.0 + screen.left;
+
+    let font = assets.font();
+    font.draw(text, (x as i32, y), Align::Center, assets, canvas);
+}
+
+/// Display the game over message with the score of every player, and the
+/// initials being entered if any
+pub fn game_over(
+    assets: &Assets,
+    scores: &[u32],
+    initials: Option<&str>,
+    screen: Screen,
+    canvas: &mut WindowCanvas,
+) {
+    let line = assets.font().height();
+    let middle = screen.height / 2 - line / 2;
+    let total: u32 = scores.iter().sum();
+
+    text_center("Game Over", middle - line * 2, assets, screen, canvas);
+    text_center(&format!("Score {total}"), middle, assets, screen, canvas);
+
+    if let [first, second] = scores {
+        let data = format!("1P {first}  2P {second}");
+        text_center(&data, middle + line, assets, screen, canvas);
+    }
+
+    if let Some(initials) = initials {
+        let data = format!("Name {initials:_<width$}", width = INITIALS);
+        text_center(&data, middle + line * 2, assets, screen, canvas);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::FULL;
+
+    /// A game of one player started from a fixed seed, with no asteroid or
+    /// enemy left to spawn so each test places its own
+    struct Game {
+        global: Global,
+        ships: Vec<Object<Ship>>,
+        asteroids: Asteroids,
+        enemies: Enemies,
+        power_ups: PowerUps,
+    }
+
+    impl Game {
+        fn new() -> Self {
+            let mut game = Game {
+                global: Global::new((500, 800), Config::default()),
+                ships: Vec::new(),
+                asteroids: Asteroids::new(),
+                enemies: Enemies::new(),
+                power_ups: PowerUps::new(),
+            };
+
+            game.global.start_game(
+                7,
+                &mut game.ships,
+                &mut game.asteroids,
+                &mut game.enemies,
+                &mut game.power_ups,
+            );
+            game.global.state = State::Default;
+            game.global.wave.budget = 0;
+            game.global.wave.enemies = 0;
+            game
+        }
+
+        /// Place a still asteroid with its top left corner at the given
+        /// offset from the ship
+        fn asteroid(&mut self, kind: Size, (dx, dy): (f32, f32)) {
+            let (x, y) = self.ships[0].pos;
+            let obj = Object::asteroid(kind, (x + dx, y + dy), (0.0, 0.0));
+            self.asteroids.objs.push(obj);
+        }
+
+        fn step(&mut self, input: Input, steps: usize) {
+            for _ in 0..steps {
+                self.global.step(
+                    &mut self.ships,
+                    &mut self.asteroids,
+                    &mut self.enemies,
+                    &mut self.power_ups,
+                    &[input],
+                );
+            }
+        }
+    }
+
+    const FIRE: Input = Input {
+        stick: (0, 0),
+        fire: true,
+    };
+
+    #[test]
+    fn bullet_scores_on_asteroid() {
+        let mut game = Game::new();
+        game.asteroid(Size::Small, (0.0, -150.0));
+
+        game.step(FIRE, 60);
+
+        assert_eq!(game.ships[0].score, game.global.config.asteroid_score[0]);
+        assert_eq!(game.ships[0].lives, 3);
+    }
+
+    #[test]
+    fn collision_costs_a_life() {
+        let mut game = Game::new();
+        game.asteroid(Size::Small, (0.0, 0.0));
+
+        game.step(Input::default(), 1);
+
+        assert_eq!(game.ships[0].lives, 2);
+        assert!(game.global.state != State::GameOver);
+    }
+
+    #[test]
+    fn last_life_ends_the_game() {
+        let mut game = Game::new();
+        game.ships[0].lives = 1;
+        game.asteroid(Size::Small, (0.0, 0.0));
+
+        game.step(Input::default(), 1);
+
+        assert_eq!(game.ships[0].lives, 0);
+        assert!(game.global.state == State::GameOver);
+    }
+
+    #[test]
+    fn ship_stays_inside_margins() {
+        let mut game = Game::new();
+        let screen = game.global.screen;
+
+        let up_left = Input {
+            stick: (-FULL, -FULL),
+            fire: false,
+        };
+        game.step(up_left, 120);
+        assert_eq!(game.ships[0].pos, (screen.left, screen.top));
+
+        let down_right = Input {
+            stick: (FULL, FULL),
+            fire: false,
+        };
+        game.step(down_right, 120);
+        let clip = game.ships[0].clip;
+        assert_eq!(
+            game.ships[0].pos,
+            (
+                screen.width as f32 - screen.right - clip.w as f32,
+                screen.height as f32 - screen.bottom - clip.h as f32,
+            )
+        );
+    }
 }
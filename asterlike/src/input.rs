@@ -0,0 +1,283 @@
+use crate::global::Screen;
+use sdl3::{
+    EventPump,
+    event::Event,
+    gamepad::{Axis, Button, Gamepad},
+    keyboard::Scancode,
+    pixels::Color,
+    render::{FPoint, WindowCanvas},
+};
+
+/// Full movement on one axis
+pub const FULL: i8 = 100;
+
+/// Stick positions closer to the center are ignored, out of `i16::MAX`
+const DEAD_ZONE: i16 = 8000;
+
+/// Distance from its center at which the virtual joystick is fully pushed,
+/// in pixels
+const JOYSTICK_RADIUS: f32 = 60.0;
+
+/// Position on the screen, in pixels
+type Spot = (f32, f32);
+
+trait Pressed<T> {
+    fn is_pressed(&self, code: T) -> bool;
+}
+
+impl Pressed<Scancode> for EventPump {
+    fn is_pressed(&self, code: Scancode) -> bool {
+        self.keyboard_state().is_scancode_pressed(code)
+    }
+}
+
+impl<P: Pressed<Scancode>> Pressed<[Scancode; 2]> for P {
+    fn is_pressed(&self, [code, code1]: [Scancode; 2]) -> bool {
+        self.is_pressed(code) || self.is_pressed(code1)
+    }
+}
+
+/// What the player asks the ship to do during one simulation step, as
+/// recorded in replays
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub(crate) struct Input {
+    /// Movement from `-FULL` to `FULL` on each axis, positive to the right
+    /// and down
+    pub(crate) stick: (i8, i8),
+    pub(crate) fire: bool,
+}
+
+/// Keys moving a ship left, right, up and down, and firing
+pub(crate) struct KeySet {
+    left: [Scancode; 2],
+    right: [Scancode; 2],
+    up: [Scancode; 2],
+    down: [Scancode; 2],
+    fire: [Scancode; 2],
+}
+
+/// Keys of a single player: the arrows or WASD, and 1 or space to fire
+pub(crate) const SOLO_KEYS: KeySet = KeySet {
+    left: [Scancode::Left, Scancode::A],
+    right: [Scancode::Right, Scancode::D],
+    up: [Scancode::Up, Scancode::W],
+    down: [Scancode::Down, Scancode::S],
+    fire: [Scancode::_1, Scancode::Space],
+};
+
+/// Keys of each player in a two-player game: WASD and space for the first,
+/// the arrows and right control or enter for the second
+pub(crate) const COOP_KEYS: [KeySet; 2] = [
+    KeySet {
+        left: [Scancode::A; 2],
+        right: [Scancode::D; 2],
+        up: [Scancode::W; 2],
+        down: [Scancode::S; 2],
+        fire: [Scancode::Space, Scancode::LCtrl],
+    },
+    KeySet {
+        left: [Scancode::Left; 2],
+        right: [Scancode::Right; 2],
+        up: [Scancode::Up; 2],
+        down: [Scancode::Down; 2],
+        fire: [Scancode::RCtrl, Scancode::Return],
+    },
+];
+
+impl Input {
+    /// Read the given keys
+    pub(crate) fn keyboard(events: &EventPump, keys: &KeySet) -> Self {
+        let axis = |less, more| match (events.is_pressed(less), events.is_pressed(more)) {
+            (true, false) => -FULL,
+            (false, true) => FULL,
+            _ => 0,
+        };
+
+        Input {
+            stick: (axis(keys.left, keys.right), axis(keys.up, keys.down)),
+            fire: events.is_pressed(keys.fire),
+        }
+    }
+
+    /// Read the left stick or the directional pad, and the south button or
+    /// the right trigger to fire
+    pub(crate) fn gamepad(pad: &Gamepad) -> Self {
+        let stick = |axis| {
+            let value = pad.axis(axis);
+            if value.unsigned_abs() < DEAD_ZONE as u16 {
+                0
+            } else {
+                (i32::from(value) * i32::from(FULL) / i32::from(i16::MAX)) as i8
+            }
+        };
+        let pad_axis = |less, more| match (pad.button(less), pad.button(more)) {
+            (true, false) => -FULL,
+            (false, true) => FULL,
+            _ => 0,
+        };
+
+        let input = Input {
+            stick: (stick(Axis::LeftX), stick(Axis::LeftY)),
+            fire: pad.button(Button::South) || pad.axis(Axis::TriggerRight) > DEAD_ZONE,
+        };
+
+        input.merge(Input {
+            stick: (
+                pad_axis(Button::DPadLeft, Button::DPadRight),
+                pad_axis(Button::DPadUp, Button::DPadDown),
+            ),
+            fire: false,
+        })
+    }
+
+    /// Combine two inputs, the strongest movement on each axis wins
+    pub(crate) fn merge(self, other: Input) -> Self {
+        let strongest = |a: i8, b: i8| {
+            if b.unsigned_abs() > a.unsigned_abs() {
+                b
+            } else {
+                a
+            }
+        };
+
+        Input {
+            stick: (
+                strongest(self.stick.0, other.stick.0),
+                strongest(self.stick.1, other.stick.1),
+            ),
+            fire: self.fire || other.fire,
+        }
+    }
+
+    /// Pack the input in an integer: the horizontal movement in the low
+    /// byte, the vertical one in the next and fire in the bit above
+    pub(crate) fn to_bits(self) -> u32 {
+        u32::from(self.stick.0 as u8)
+            | u32::from(self.stick.1 as u8) << 8
+            | u32::from(self.fire) << 16
+    }
+
+    pub(crate) fn from_bits(bits: u32) -> Self {
+        Input {
+            stick: (bits as u8 as i8, (bits >> 8) as u8 as i8),
+            fire: bits & 1 << 16 != 0,
+        }
+    }
+}
+
+/// On-screen controls for touch screens: a virtual joystick appears where
+/// the left half of the screen is touched, the right half fires
+#[derive(Default)]
+pub(crate) struct Touch {
+    /// Finger on the joystick, its center and where it is now
+    joystick: Option<(u64, Spot, Spot)>,
+    /// Finger on the fire button
+    fire: Option<u64>,
+    /// Shown once the screen has been touched
+    active: bool,
+}
+
+impl Touch {
+    /// Follow the fingers on the screen
+    pub(crate) fn event(&mut self, event: &Event, screen: Screen) {
+        let (width, height) = (screen.width as f32, screen.height as f32);
+
+        match *event {
+            Event::FingerDown {
+                finger_id, x, y, ..
+            } => {
+                let at = (x * width, y * height);
+                self.active = true;
+
+                if at.0 < width / 2.0 {
+                    self.joystick = Some((finger_id, at, at));
+                } else {
+                    self.fire = Some(finger_id);
+                }
+            }
+            Event::FingerMotion {
+                finger_id, x, y, ..
+            } => {
+                if let Some((id, _, at)) = &mut self.joystick
+                    && *id == finger_id
+                {
+                    *at = (x * width, y * height);
+                }
+            }
+            Event::FingerUp { finger_id, .. } => {
+                if self.joystick.is_some_and(|(id, ..)| id == finger_id) {
+                    self.joystick = None;
+                }
+
+                if self.fire == Some(finger_id) {
+                    self.fire = None;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pub(crate) fn input(&self) -> Input {
+        let stick = self.joystick.map_or((0, 0), |(_, center, at)| {
+            let axis = |from: f32, to: f32| {
+                let push = ((to - from) / JOYSTICK_RADIUS).clamp(-1.0, 1.0);
+                (push * f32::from(FULL)) as i8
+            };
+            (axis(center.0, at.0), axis(center.1, at.1))
+        });
+
+        Input {
+            stick,
+            fire: self.fire.is_some(),
+        }
+    }
+
+    /// Draw the joystick while held and the fire button
+    pub(crate) fn draw(&self, screen: Screen, canvas: &mut WindowCanvas) {
+        if !self.active {
+            return;
+        }
+
+        let ring = |center: Spot, radius: f32| -> Vec<FPoint> {
+            (0..64)
+                .map(|i| {
+                    let (sin, cos) = (i as f32 * std::f32::consts::TAU / 64.0).sin_cos();
+                    FPoint::new(center.0 + radius * cos, center.1 + radius * sin)
+                })
+                .collect()
+        };
+
+        canvas.set_draw_color(Color::from((0x80, 0x80, 0x80, 255)));
+
+        if let Some((_, center, at)) = self.joystick {
+            canvas
+                .draw_points(&ring(center, JOYSTICK_RADIUS)[..])
+                .unwrap();
+
+            let (sin, cos) = (at.1 - center.1).atan2(at.0 - center.0).sin_cos();
+            let reach = (at.0 - center.0)
+                .hypot(at.1 - center.1)
+                .min(JOYSTICK_RADIUS);
+            let knob = (center.0 + reach * cos, center.1 + reach * sin);
+            canvas
+                .draw_points(&ring(knob, JOYSTICK_RADIUS / 3.0)[..])
+                .unwrap();
+        }
+
+        let button = (
+            screen.width as f32 - JOYSTICK_RADIUS * 2.0,
+            screen.height as f32 - JOYSTICK_RADIUS * 2.0,
+        );
+
+        if self.fire.is_some() {
+            canvas
+                .draw_points(&ring(button, JOYSTICK_RADIUS / 2.0)[..])
+                .unwrap();
+        }
+
+        canvas
+            .draw_points(&ring(button, JOYSTICK_RADIUS)[..])
+            .unwrap();
+        canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
+    }
+}
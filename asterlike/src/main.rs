@@ -1,19 +1,55 @@
+mod assets;
+mod audio;
+mod boss;
+mod collision;
+mod config;
+mod enemy;
+mod font;
 mod global;
+mod input;
+mod menu;
 mod object;
+mod particle;
+mod powerup;
+mod replay;
+mod score;
+mod starfield;
+mod wave;
 mod wrappers;
 
 use crate::{
-    global::{Global, State, game_over, init, text_middle},
-    object::{Asteroids, Object, Ship, Size, Text},
+    assets::{Assets, TextureId},
+    audio::Audio,
+    collision::Mask,
+    config::Config,
+    enemy::Enemies,
+    global::{Global, Options, STEPS_PER_SECOND, Screen, State, game_over, init, text_middle},
+    input::{COOP_KEYS, Input, SOLO_KEYS, Touch},
+    menu::{Action, Menu},
+    object::{Asteroids, Object, Ship, total_score},
+    particle::Particles,
+    powerup::PowerUps,
+    replay::Replay,
+    score::{HighScores, INITIALS},
 };
+use clap::Parser;
 use sdl3::{
     event::{Event, WindowEvent},
+    gamepad::Gamepad,
     image::LoadSurface,
     keyboard::Keycode,
     rect::Rect,
-    render::{Texture, WindowCanvas},
+    render::WindowCanvas,
     surface::Surface,
 };
+use std::{
+    io,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+/// Longest frame time simulated, so a stalled frame doesn't snowball
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
 
 #[cfg(target_os = "emscripten")]
 mod emscripten {
@@ -31,22 +67,100 @@ mod emscripten {
     }
 }
 
+/// A vertical asteroid shooter
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// Records the inputs of every game to the given file, overwriting it
+    #[arg(long = "record", value_name = "FILE")]
+    record: Option<PathBuf>,
+
+    /// Plays back a game recorded with `--record`
+    #[arg(long = "replay", value_name = "FILE", conflicts_with = "record")]
+    replay: Option<PathBuf>,
+
+    /// Simulates the replay without opening a window and prints its outcome
+    #[arg(long = "headless", requires = "replay")]
+    headless: bool,
+
+    /// Reads the game balance from the given file instead of the
+    /// `asterlike.cfg` next to the binary
+    #[arg(long = "config", value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Overrides a setting of the config file, can be repeated
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    set: Vec<String>,
+
+    /// Starts in a window instead of fullscreen
+    #[arg(long = "windowed", conflicts_with = "fullscreen")]
+    windowed: bool,
+
+    /// Starts fullscreen, falling back to a window if it fails
+    #[arg(long = "fullscreen")]
+    fullscreen: bool,
+
+    /// Size of the window when not fullscreen
+    #[arg(long = "size", value_name = "WIDTHxHEIGHT", value_parser = parse_size)]
+    size: Option<(u32, u32)>,
+
+    /// Waits for the display refresh between frames
+    #[arg(long = "vsync")]
+    vsync: bool,
+
+    /// SDL render driver to use, such as software, opengl or vulkan
+    #[arg(long = "render-driver", value_name = "NAME")]
+    render_driver: Option<String>,
+}
+
+/// Parse a window size such as `800x600`
+fn parse_size(size: &str) -> Result<(u32, u32), String> {
+    size.split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+        .filter(|&(w, h)| w > 0 && h > 0)
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {size}"))
+}
+
 struct Game {
     _context: sdl3::Sdl,
     event_pump: sdl3::EventPump,
+    gamepad: Option<sdl3::GamepadSubsystem>,
+    gamepads: Vec<Gamepad>,
+    touch: Touch,
     global: Global,
     canvas: WindowCanvas,
-    sprite_sheet: &'static Texture,
-    font_large: &'static Texture,
-    asteroids: Asteroids<'static>,
-    ship: Object<'static, Ship<'static>>,
+    assets: Assets,
+    asteroids: Asteroids,
+    ships: Vec<Object<Ship>>,
+    enemies: Enemies,
+    power_ups: PowerUps,
+    particles: Particles,
+    menu: Menu,
+    scores: HighScores,
+    audio: Audio,
+    /// Initials typed on the game over screen for a new high score
+    initials: Option<String>,
+    /// Settings loaded at startup, a replay plays with its own game balance
+    config: Config,
+    /// Latest output size of the window, applied to the screen between games
+    window_size: (u32, u32),
+    /// Real time not yet simulated
+    accumulator: Duration,
+    last_frame: Instant,
+    /// Simulation steps run in the current game
+    step: usize,
+    /// File where games are recorded
+    record: Option<PathBuf>,
+    recording: Option<Replay>,
+    playback: Option<Replay>,
 }
 
 impl Game {
-    fn new() -> Self {
+    fn new(args: Args, config: Config) -> Self {
+        let settings = config.clone();
         // Setup Pre-Game Logic and Constants
         let icon = Surface::from_file("assets/img/icon.bmp").unwrap();
-        let (context, mut win) = init();
+        let (context, mut win) = init(&config.video);
 
         win.set_icon(icon);
 
@@ -54,149 +168,536 @@ impl Game {
             .event_pump()
             .expect("unable to obtain event pump handle!");
 
-        // Fullscreen on the web requires a user gesture; don't hard-fail.
-        #[cfg(target_os = "emscripten")]
-        {
-            let _ = win.set_fullscreen(true);
-        }
-        #[cfg(not(target_os = "emscripten"))]
-        {
-            win.set_fullscreen(true)
-                .expect("unable to set fullscreen mode!");
-        }
+        // Gamepads are optional, connected ones are opened as they show up
+        let gamepad = context
+            .gamepad()
+            .inspect_err(|err| eprintln!("gamepads disabled: {err}"))
+            .ok();
+
+        // Fullscreen can fail, on the web it requires a user gesture
+        let fullscreen = config.video.fullscreen
+            && win
+                .set_fullscreen(true)
+                .inspect_err(|err| {
+                    eprintln!("unable to set fullscreen mode, using a window: {err}")
+                })
+                .is_ok();
 
-        let mut global = Global::new(win.size());
-        let mut canvas = win.into_canvas();
+        let mut global = Global::new(win.size(), config);
+        global.options.fullscreen = fullscreen;
+        global.mask = load_mask();
+        let canvas = win.into_canvas();
 
         // Sync to the renderer's real output size (important for fullscreen / HiDPI).
         if let Ok(size) = canvas.output_size() {
             global.update_screen_size(size);
         }
+        let Screen { width, height, .. } = global.screen;
 
-        let creator = canvas.texture_creator();
-
-        // Leak textures so we can keep stable references inside game objects.
-        let sprite_sheet: &'static Texture = Box::leak(Box::new(wrappers::load_texture(
-            "assets/img/sprite-sheet.bmp",
-            &creator,
-            (0x0, 0x0, 0x0),
-        )));
-        let font_large: &'static Texture = Box::leak(Box::new(wrappers::load_texture(
-            "assets/fonts/fontx64.bmp",
-            &creator,
-            (0x0, 0x0, 0x0),
-        )));
-
-        let asteroids = Asteroids::new(sprite_sheet);
-        let mut ship = Object::new(
-            Ship {
-                lives: 3,
-                bullets: Vec::new(),
-            },
-            sprite_sheet,
-            0,
-            Rect::new(0, 0, 32, 32),
-            1.0,
-        );
+        let assets = Assets::new(canvas.texture_creator());
 
-        let screen = global.screen;
-        ship.position((screen.width / 2, screen.height / 2), &mut canvas);
-        global.timer.bullet = sdl3::timer::ticks();
+        let audio = Audio::new(&context, global.options.volume);
+        audio.play_music();
+
+        let asteroids = Asteroids::new();
 
         Game {
             _context: context,
             event_pump,
+            gamepad,
+            gamepads: Vec::new(),
+            touch: Touch::default(),
             global,
             canvas,
-            sprite_sheet,
-            font_large,
+            assets,
             asteroids,
-            ship,
+            ships: Vec::new(),
+            enemies: Enemies::new(),
+            power_ups: PowerUps::new(),
+            particles: Particles::new(),
+            menu: Menu::new(),
+            scores: HighScores::load(),
+            audio,
+            initials: None,
+            window_size: (width as u32, height as u32),
+            config: settings,
+            accumulator: Duration::ZERO,
+            last_frame: Instant::now(),
+            step: 0,
+            record: args.record,
+            recording: None,
+            playback: None,
+        }
+    }
+
+    /// Start playing back a recorded game
+    fn replay(&mut self, replay: Replay) {
+        self.playback = Some(replay);
+        self.start();
+    }
+
+    /// Start a new game from the menu, or the game being played back
+    fn start(&mut self) {
+        let seed = match &self.playback {
+            Some(replay) => {
+                self.global.options.difficulty = replay.difficulty;
+                self.global.options.players = replay.players;
+                self.global.update_screen_size(replay.screen);
+                self.global.config = Config {
+                    video: self.config.video.clone(),
+                    ..replay.config.clone()
+                };
+                replay.seed
+            }
+            None => {
+                self.global.config = self.config.clone();
+                rand::random()
+            }
+        };
+
+        self.step = 0;
+        if self.record.is_some() && self.playback.is_none() {
+            let Screen { width, height, .. } = self.global.screen;
+            let screen = (width as u32, height as u32);
+            let Options {
+                difficulty,
+                players,
+                ..
+            } = self.global.options;
+            let config = self.global.config.clone();
+            self.recording = Some(Replay::new(seed, difficulty, screen, players, config));
+        }
+
+        self.global.start_game(
+            seed,
+            &mut self.ships,
+            &mut self.asteroids,
+            &mut self.enemies,
+            &mut self.power_ups,
+        );
+        self.particles = Particles::new();
+    }
+
+    /// Apply the fullscreen option, reverting it if the window refuses
+    fn set_fullscreen(&mut self) {
+        let fullscreen = self.global.options.fullscreen;
+
+        if let Err(err) = self.canvas.window_mut().set_fullscreen(fullscreen) {
+            eprintln!("unable to change fullscreen mode: {err}");
+            self.global.options.fullscreen = !fullscreen;
+        }
+
+        if let Ok(size) = self.canvas.output_size() {
+            self.resize(size);
+        }
+    }
+
+    /// Follow the size of the window, the screen of a game being played or
+    /// played back stays the same until it ends so replays don't diverge
+    fn resize(&mut self, size: (u32, u32)) {
+        self.window_size = size;
+
+        if !matches!(
+            self.global.state,
+            State::Default | State::Wave | State::Pause
+        ) {
+            self.global.update_screen_size(size);
+        }
+    }
+
+    /// Save the recording of the game that just ended and stop any playback
+    fn end_game(&mut self) {
+        if let (Some(path), Some(recording)) = (&self.record, self.recording.take())
+            && let Err(err) = recording.save(path)
+        {
+            eprintln!("unable to save replay to {}: {err}", path.display());
+        }
+
+        self.playback = None;
+        self.global.update_screen_size(self.window_size);
+    }
+
+    /// Handle a key press on the game over screen
+    fn game_over_key(&mut self, kode: Keycode) {
+        let Some(initials) = self.initials.as_mut() else {
+            if matches!(kode, Keycode::Return | Keycode::KpEnter | Keycode::Escape) {
+                self.global.state = State::Menu;
+            }
+            return;
+        };
+
+        match kode {
+            Keycode::Return | Keycode::KpEnter if initials.len() == INITIALS => {
+                self.scores.insert(initials, total_score(&self.ships));
+                self.scores.save();
+                self.initials = None;
+                self.menu.show_scores();
+                self.global.state = State::Menu;
+            }
+            Keycode::Backspace => {
+                initials.pop();
+            }
+            Keycode::Escape => {
+                self.initials = None;
+                self.global.state = State::Menu;
+            }
+            _ => {
+                let name = kode.name();
+                if let [letter] = name.as_bytes()
+                    && letter.is_ascii_alphabetic()
+                    && initials.len() < INITIALS
+                {
+                    initials.push(letter.to_ascii_uppercase() as char);
+                }
+            }
         }
     }
 
     fn tick(&mut self) {
         let frame_start = sdl3::timer::ticks();
-        self.global.timer.global = frame_start;
+        let now = Instant::now();
 
-        self.canvas.clear();
+        // Run as many simulation steps as the elapsed time requires
+        self.accumulator += now.duration_since(self.last_frame).min(MAX_FRAME_TIME);
+        self.last_frame = now;
+
+        let mut action = Action::None;
 
-        for event in self.event_pump.poll_iter() {
+        let events: Vec<Event> = self.event_pump.poll_iter().collect();
+
+        for event in events {
             match event {
-                Event::Quit { .. } => self.global.exit = true,
-                Event::Window { win_event, .. } => match win_event {
-                    WindowEvent::Resized(w, h) | WindowEvent::PixelSizeChanged(w, h) => {
-                        let w = u32::try_from(w).unwrap_or(1).max(1);
-                        let h = u32::try_from(h).unwrap_or(1).max(1);
-                        self.global.update_screen_size((w, h));
+                Event::Quit { .. } => {
+                    // Keep the recording of a game left by closing the window
+                    self.end_game();
+                    self.global.exit = true;
+                }
+                Event::GamepadAdded { which, .. } => {
+                    if let Some(gamepad) = &self.gamepad {
+                        match gamepad.open(which) {
+                            Ok(pad) => self.gamepads.push(pad),
+                            Err(err) => eprintln!("unable to open gamepad: {err}"),
+                        }
                     }
-                    _ => (),
-                },
+                }
+                Event::GamepadRemoved { which, .. } => {
+                    self.gamepads.retain(|pad| pad.id().ok() != Some(which));
+                }
+                Event::FingerDown { .. } | Event::FingerMotion { .. } | Event::FingerUp { .. } => {
+                    self.touch.event(&event, self.global.screen);
+                }
+                // The textures are gone with the old rendering device
+                Event::RenderDeviceReset { .. } => self.assets.reload(),
+                Event::Window {
+                    win_event: WindowEvent::Resized(w, h) | WindowEvent::PixelSizeChanged(w, h),
+                    ..
+                } => {
+                    let w = u32::try_from(w).unwrap_or(1).max(1);
+                    let h = u32::try_from(h).unwrap_or(1).max(1);
+                    self.resize((w, h));
+                }
                 Event::KeyDown {
                     repeat: false,
                     keycode: Some(kode),
                     ..
-                } => match kode {
-                    Keycode::P => {
-                        self.global.state = if self.global.state == State::Default {
-                            State::Pause
-                        } else {
-                            State::Default
-                        };
+                } => match (self.global.state, kode) {
+                    (State::Menu, _) => action = self.menu.key(kode, &mut self.global.options),
+                    (State::GameOver, _) => self.game_over_key(kode),
+                    (State::Default, Keycode::P) => self.global.state = State::Pause,
+                    (State::Pause, Keycode::P) => self.global.state = State::Default,
+                    (_, Keycode::Escape) => {
+                        self.end_game();
+                        self.global.state = State::Menu;
                     }
-                    Keycode::Escape => self.global.exit = true,
                     _ => (),
                 },
                 _ => (),
             }
         }
 
+        match action {
+            Action::Start => self.start(),
+            Action::Quit => self.global.exit = true,
+            Action::Fullscreen => self.set_fullscreen(),
+            Action::Volume => self.audio.set_volume(self.global.options.volume),
+            Action::None => (),
+        }
+
         self.event_pump.pump_events();
-        self.global.timer.game = sdl3::timer::ticks() / 1000;
 
-        match self.global.state {
-            State::Default => {
-                if self.ship.lives < 1 {
-                    self.global.exit = true;
+        let step = Duration::from_secs(1) / STEPS_PER_SECOND;
+        while self.accumulator >= step {
+            self.accumulator -= step;
+            self.update();
+        }
+
+        self.canvas.clear();
+        self.draw();
+
+        for sound in self.global.sounds.drain(..) {
+            self.audio.play(sound);
+        }
+
+        self.canvas.present();
+        self.global.delay(frame_start);
+    }
+
+    /// Read the input of each player: alone, the keyboard, every gamepad
+    /// and the touch controls move the ship; together, each player has their
+    /// own keys and gamepads, the touch controls go to the first one
+    fn capture(&self) -> Vec<Input> {
+        let players = self.global.options.players;
+
+        (0..players)
+            .map(|player| {
+                let keys = if players == 1 {
+                    &SOLO_KEYS
                 } else {
-                    let screen = self.global.screen;
-                    self.ship.update((
-                        screen,
-                        self.global.speed,
-                        &mut self.global.timer,
-                        &self.event_pump,
+                    &COOP_KEYS[player]
+                };
+                let input = self
+                    .gamepads
+                    .iter()
+                    .skip(player)
+                    .step_by(players)
+                    .map(Input::gamepad)
+                    .fold(Input::keyboard(&self.event_pump, keys), Input::merge);
+
+                match player {
+                    0 => input.merge(self.touch.input()),
+                    _ => input,
+                }
+            })
+            .collect()
+    }
+
+    /// Advance the game by one simulation step
+    fn update(&mut self) {
+        if !matches!(self.global.state, State::Default | State::Wave) {
+            return;
+        }
+
+        let inputs = match &self.playback {
+            Some(replay) => match replay.inputs.get(self.step) {
+                Some(inputs) => inputs.clone(),
+                None => {
+                    // The recording stopped before the game was over
+                    self.end_game();
+                    self.global.state = State::Menu;
+                    return;
+                }
+            },
+            None => self.capture(),
+        };
+
+        self.step += 1;
+        if let Some(recording) = &mut self.recording {
+            recording.inputs.push(inputs.clone());
+        }
+
+        self.global.step(
+            &mut self.ships,
+            &mut self.asteroids,
+            &mut self.enemies,
+            &mut self.power_ups,
+            &inputs,
+        );
+
+        self.global.stars.update(self.global.speed.asteroid);
+        for effect in self.global.effects.drain(..) {
+            self.particles.apply(effect);
+        }
+        self.particles.update();
+
+        if self.global.state == State::GameOver {
+            let score = total_score(&self.ships);
+            self.initials =
+                (self.playback.is_none() && self.scores.qualifies(score)).then(String::new);
+            self.end_game();
+        }
+    }
+
+    /// Draw the current state of the game
+    fn draw(&mut self) {
+        let screen = self.global.screen;
+        self.global.stars.draw(&mut self.canvas);
+
+        match self.global.state {
+            State::Default | State::Wave | State::Pause => {
+                // Shake the playfield but not the HUD
+                if self.global.state != State::Pause {
+                    let (x, y) = self.particles.shake_offset();
+                    let Screen { width, height, .. } = screen;
+                    self.canvas
+                        .set_viewport(Rect::new(x, y, width as u32, height as u32));
+                }
+
+                let sprites = self.assets.get(TextureId::SpriteSheet);
+                self.asteroids.draw(sprites, &mut self.canvas);
+                self.enemies.draw(sprites, &mut self.canvas);
+                self.power_ups.draw(sprites, &mut self.canvas);
+                for (player, ship) in self.ships.iter().enumerate() {
+                    ship.render(
+                        player,
+                        self.assets.get_mut(TextureId::SpriteSheet),
                         &mut self.canvas,
-                    ));
-                    self.asteroids
-                        .update(screen, &mut self.global.speed, &mut self.canvas);
-                    self.global.collision(&mut self.ship, &mut self.asteroids);
-                    self.global
-                        .hud(&self.ship, self.font_large, &mut self.canvas);
+                    );
                 }
-            }
-            State::Pause => {
-                let screen = self.global.screen;
-                let text = Text::new(self.font_large, "Pause", Size::Large, 1.0);
-                text_middle(text, screen, &mut self.canvas);
+                self.particles.draw(
+                    self.assets.get_mut(TextureId::SpriteSheet),
+                    &mut self.canvas,
+                );
+                self.canvas.set_viewport(None);
+
+                let boss = self.enemies.boss.as_ref();
                 self.global
-                    .hud(&self.ship, self.font_large, &mut self.canvas);
+                    .hud(&self.ships, boss, &self.assets, &mut self.canvas);
+                self.touch.draw(screen, &mut self.canvas);
+
+                let message = match self.global.state {
+                    State::Wave => format!("Wave {}", self.global.wave.number),
+                    State::Pause => "Pause".to_string(),
+                    _ => return,
+                };
+                text_middle(&message, &self.assets, screen, &mut self.canvas);
+            }
+            State::GameOver => {
+                let scores: Vec<u32> = self.ships.iter().map(|ship| ship.score).collect();
+                game_over(
+                    &self.assets,
+                    &scores,
+                    self.initials.as_deref(),
+                    screen,
+                    &mut self.canvas,
+                );
             }
             State::Menu => {
-                //
+                self.menu.draw(
+                    &self.global.options,
+                    &self.scores,
+                    &self.assets,
+                    screen,
+                    &mut self.canvas,
+                );
             }
-            _ => unimplemented!(),
         }
+    }
+}
 
-        self.canvas.present();
-        self.global.delay(frame_start);
+/// Load the opaque pixels of the sprite sheet, without them pixel colliders
+/// fall back to bounding boxes
+fn load_mask() -> Mask {
+    let sheet = TextureId::SpriteSheet;
+    Mask::load(sheet.path(), sheet.color_key()).unwrap_or_else(|err| {
+        eprintln!("unable to load collision mask: {err}");
+        Mask::default()
+    })
+}
+
+/// Simulate a recorded game without SDL video, until its inputs run out or
+/// every player is out of lives, with the game balance it was recorded with
+fn headless(replay: &Replay) {
+    let mut global = Global::new(replay.screen, replay.config.clone());
+    global.mask = load_mask();
+    let mut ships = Vec::new();
+    let mut asteroids = Asteroids::new();
+    let mut enemies = Enemies::new();
+    let mut power_ups = PowerUps::new();
 
-        if self.global.exit && self.ship.lives < 1 {
-            game_over(self.font_large, self.global.screen, &mut self.canvas);
+    global.options.difficulty = replay.difficulty;
+    global.options.players = replay.players;
+    global.start_game(
+        replay.seed,
+        &mut ships,
+        &mut asteroids,
+        &mut enemies,
+        &mut power_ups,
+    );
+
+    let mut steps = 0;
+    for inputs in &replay.inputs {
+        global.step(
+            &mut ships,
+            &mut asteroids,
+            &mut enemies,
+            &mut power_ups,
+            inputs,
+        );
+        global.sounds.clear();
+        global.effects.clear();
+        steps += 1;
+
+        if global.state == State::GameOver {
+            break;
         }
     }
+
+    println!(
+        "steps {steps}\nscore {}\nlives {}\nwave {}\nbosses {}\ngame over {}",
+        total_score(&ships),
+        ships
+            .iter()
+            .map(|ship| ship.lives.to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        global.wave.number,
+        global.bosses,
+        global.state == State::GameOver
+    );
+}
+
+/// Load the config file and apply the overrides given on the command line
+fn load_config(args: &Args) -> io::Result<Config> {
+    let mut config = Config::load(args.config.as_deref())?;
+
+    for setting in &args.set {
+        let (key, value) = setting.split_once('=').ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("expected KEY=VALUE: {setting}"),
+            )
+        })?;
+        config.set(key.trim(), value.trim())?;
+    }
+
+    let video = &mut config.video;
+    if args.windowed || args.fullscreen {
+        video.fullscreen = args.fullscreen;
+    }
+    video.size = args.size.unwrap_or(video.size);
+    video.vsync |= args.vsync;
+    if let Some(driver) = &args.render_driver {
+        video.driver = Some(driver.clone());
+    }
+
+    Ok(config)
 }
 
 fn main() {
+    let mut args = Args::parse();
+    let config = load_config(&args).unwrap_or_else(|err| {
+        eprintln!("unable to load config: {err}");
+        std::process::exit(1);
+    });
+    let replay = args.replay.take().map(|path| {
+        Replay::load(&path).unwrap_or_else(|err| {
+            eprintln!("unable to load replay {}: {err}", path.display());
+            std::process::exit(1);
+        })
+    });
+
+    if args.headless {
+        if let Some(replay) = &replay {
+            headless(replay);
+        }
+        return;
+    }
+
+    let mut game = Game::new(args, config);
+    if let Some(replay) = replay {
+        game.replay(replay);
+    }
+
     #[cfg(target_os = "emscripten")]
     {
         use core::ffi::c_void;
@@ -209,7 +710,7 @@ fn main() {
             }
         }
 
-        let game = Box::new(Game::new());
+        let game = Box::new(game);
         let game_ptr = Box::into_raw(game) as *mut c_void;
 
         unsafe {
@@ -223,7 +724,6 @@ fn main() {
 
     #[cfg(not(target_os = "emscripten"))]
     {
-        let mut game = Game::new();
         while !game.global.exit {
             game.tick();
         }
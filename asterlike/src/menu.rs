@@ -0,0 +1,195 @@
+use crate::{
+    assets::Assets,
+    global::{Options, Screen, text_center},
+    score::HighScores,
+};
+use sdl3::{keyboard::Keycode, pixels::Color, rect::Rect, render::WindowCanvas};
+
+/// What the game should do after a key press in the menu
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Action {
+    None,
+    Start,
+    Quit,
+    Fullscreen,
+    Volume,
+}
+
+/// Menu pages
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Page {
+    Title,
+    Options,
+    Scores,
+}
+
+const TITLE_ITEMS: usize = 4;
+const OPTION_ITEMS: usize = 5;
+
+/// Title menu and its options page
+pub struct Menu {
+    page: Page,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Menu {
+            page: Page::Title,
+            selected: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self.page {
+            Page::Title => TITLE_ITEMS,
+            Page::Options => OPTION_ITEMS,
+            Page::Scores => 1,
+        }
+    }
+
+    fn open(&mut self, page: Page) {
+        self.page = page;
+        self.selected = 0;
+    }
+
+    /// Show the high-score table, used after entering a new score
+    pub fn show_scores(&mut self) {
+        self.open(Page::Scores);
+    }
+
+    /// Handle a key press, changing the options in place
+    pub fn key(&mut self, key: Keycode, options: &mut Options) -> Action {
+        match key {
+            Keycode::Up | Keycode::W => {
+                self.selected = (self.selected + self.len() - 1) % self.len();
+            }
+            Keycode::Down | Keycode::S => {
+                self.selected = (self.selected + 1) % self.len();
+            }
+            Keycode::Escape => match self.page {
+                Page::Title => return Action::Quit,
+                Page::Options | Page::Scores => self.open(Page::Title),
+            },
+            Keycode::Left | Keycode::A if self.page == Page::Options => {
+                return self.change(options, false);
+            }
+            Keycode::Right | Keycode::D if self.page == Page::Options => {
+                return self.change(options, true);
+            }
+            Keycode::Return | Keycode::KpEnter | Keycode::Space => match (self.page, self.selected)
+            {
+                (Page::Title, 0) => return Action::Start,
+                (Page::Title, 1) => self.open(Page::Scores),
+                (Page::Title, 2) => self.open(Page::Options),
+                (Page::Title, _) => return Action::Quit,
+                (Page::Options, 4) | (Page::Scores, _) => self.open(Page::Title),
+                (Page::Options, _) => return self.change(options, true),
+            },
+            _ => (),
+        }
+
+        Action::None
+    }
+
+    /// Change the selected option up or down
+    fn change(&mut self, options: &mut Options, up: bool) -> Action {
+        match self.selected {
+            0 => {
+                options.volume = if up {
+                    (options.volume + 1).min(Options::MAX_VOLUME)
+                } else {
+                    options.volume.saturating_sub(1)
+                };
+                return Action::Volume;
+            }
+            1 if up => options.difficulty = options.difficulty.next(),
+            1 => options.difficulty = options.difficulty.previous(),
+            2 => {
+                options.fullscreen = !options.fullscreen;
+                return Action::Fullscreen;
+            }
+            3 if up => options.players = (options.players + 1).min(Options::MAX_PLAYERS),
+            3 => options.players = (options.players - 1).max(1),
+            _ => (),
+        }
+
+        Action::None
+    }
+
+    /// Display the current menu page
+    pub fn draw(
+        &self,
+        options: &Options,
+        scores: &HighScores,
+        assets: &Assets,
+        screen: Screen,
+        canvas: &mut WindowCanvas,
+    ) {
+        if self.page == Page::Scores {
+            return draw_scores(scores, assets, screen, canvas);
+        }
+
+        let items = match self.page {
+            Page::Title => vec![
+                "Start".to_string(),
+                "Scores".to_string(),
+                "Options".to_string(),
+                "Quit".to_string(),
+            ],
+            Page::Options => vec![
+                format!("Volume {}", options.volume),
+                format!("Skill {}", options.difficulty.name()),
+                format!(
+                    "Fullscreen {}",
+                    if options.fullscreen { "On" } else { "Off" }
+                ),
+                format!("Players {}", options.players),
+                "Back".to_string(),
+            ],
+            Page::Scores => unreachable!(),
+        };
+        let line = assets.font().height();
+        let title = match self.page {
+            Page::Options => "Options",
+            _ => "Asterlike",
+        };
+
+        text_center(title, screen.height / 4 - line / 2, assets, screen, canvas);
+
+        let top = screen.height / 2 - line * items.len() as i32 / 2;
+
+        for (i, item) in items.iter().enumerate() {
+            let y = top + line * i as i32;
+
+            // Highlight the selected item
+            if i == self.selected {
+                canvas.set_draw_color(Color::from((0, 51, 102, 255)));
+                canvas
+                    .fill_rect(Rect::new(0, y, screen.width as u32, line as u32))
+                    .unwrap();
+                canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
+            }
+
+            text_center(item, y, assets, screen, canvas);
+        }
+    }
+}
+
+/// Display the high-score table
+fn draw_scores(scores: &HighScores, assets: &Assets, screen: Screen, canvas: &mut WindowCanvas) {
+    let top = screen.top as i32;
+    let line = ((screen.height - top) / (HighScores::MAX as i32 + 2)).min(assets.font().height());
+
+    text_center("High Scores", top, assets, screen, canvas);
+
+    if scores.entries().is_empty() {
+        return text_center("No scores yet", top + line * 2, assets, screen, canvas);
+    }
+
+    for (i, entry) in scores.entries().iter().enumerate() {
+        let data = format!("{:>2} {} {:>6}", i + 1, entry.initials, entry.score);
+        let y = top + line * (i as i32 + 2);
+        text_center(&data, y, assets, screen, canvas);
+    }
+}
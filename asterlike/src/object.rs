@@ -1,20 +1,27 @@
-use crate::global::{Screen, Speed, Timer, rand};
+use crate::{
+    collision::{Collider, Mask},
+    config::Config,
+    global::{GameRng, STEP, Screen, Speed, Timer},
+    input::{FULL, Input},
+    powerup::{DURATION, PowerUp},
+    wave::Wave,
+};
 use sdl3::{
-    EventPump,
-    keyboard::Scancode,
+    pixels::Color,
     rect::{Point, Rect},
     render::{FPoint, FRect, Texture, WindowCanvas},
 };
 
 use std::ops::{Deref, DerefMut};
 
-type ScreenItems<'a> = (
-    Screen,
-    Speed,
-    &'a mut Timer,
-    &'a EventPump,
-    &'a mut WindowCanvas,
-);
+type ScreenItems<'a> = (Screen, Speed, &'a Timer, &'a Input, &'a Config);
+
+/// Sideways speed of the outer bullets of a spread shot, relative to their
+/// upward speed
+const SPREAD_DRIFT: f32 = 0.25;
+
+/// Color each player's ship and bullets are tinted with
+const PLAYER_TINTS: [(u8, u8, u8); 2] = [(0xff, 0xff, 0xff), (0xff, 0xa0, 0x60)];
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 #[allow(dead_code)]
@@ -25,96 +32,183 @@ pub enum Size {
     Large = 64,
 }
 
-/// Definition for game objects
-pub struct Object<'tex, T> {
+/// Definition for game objects, the texture they are drawn from is only
+/// given when drawing so they can be simulated without a renderer
+pub struct Object<T> {
     this: T,
     // kind: ObjKind,
     // lives: i16,
     // next: Option<Box<Object>>
-    image: &'tex Texture,
     pub clip: Rect,
     // sub_image,
     sub_image: u16, // Necesario para text?, sera movido de otra forma!
-    //     x    y
-    pub pos: (i32, i32),
+    /// Top left corner, x and y with sub-pixel precision
+    pub pos: (f32, f32),
     pub scale: f32,
+    /// Shape tested once the bounding boxes overlap
+    pub collider: Collider,
 }
 
-impl<'tex, T> Object<'tex, T> {
-    pub fn new(this: T, image: &'tex Texture, sub_image: u16, clip: Rect, scale: f32) -> Self {
+impl<T> Object<T> {
+    pub fn new(this: T, sub_image: u16, clip: Rect, scale: f32) -> Self {
         Object {
             this,
-            image,
             sub_image, // : 0?
-            pos: (0, 0),
+            pos: (0.0, 0.0),
             scale,
             clip,
+            collider: Collider::Box,
         }
     }
 
-    pub fn clip(&self) -> Rect {
-        self.clip
+    /// Center of the bounding box
+    pub fn center(&self) -> (f32, f32) {
+        (
+            self.pos.0 + self.clip.w as f32 * self.scale / 2.0,
+            self.pos.1 + self.clip.h as f32 * self.scale / 2.0,
+        )
     }
 
-    pub fn scale(&self) -> f32 {
-        self.scale
+    /// Place the center of the bounding box on the given point
+    pub fn center_on(&mut self, (x, y): (f32, f32)) {
+        self.pos = (
+            x - self.clip.w as f32 * self.scale / 2.0,
+            y - self.clip.h as f32 * self.scale / 2.0,
+        );
     }
 
-    pub fn image(&self) -> &'tex Texture {
-        self.image
+    /// Move an object relative coordinates (x and y)
+    pub fn translate(&mut self, pos: (f32, f32)) {
+        self.pos.0 += pos.0;
+        self.pos.1 += pos.1;
     }
 
-    pub fn sub_image(&self) -> u16 {
-        self.sub_image
+    /// Move an object for one simulation step at the given velocity, in
+    /// pixels per second
+    pub fn advance(&mut self, (vx, vy): (f32, f32)) {
+        self.translate((vx * STEP, vy * STEP));
     }
 
-    /// Move an object relative coordinates (x and y) and set new object coordinates
-    pub fn position(&mut self, pos: (i32, i32), canvas: &mut WindowCanvas) {
+    /// Draw the current sub image at the object coordinates
+    pub fn draw(&self, image: &Texture, canvas: &mut WindowCanvas) {
         let mut clip = self.clip;
-        self.pos.0 += pos.0;
-        self.pos.1 += pos.1;
         clip.x += clip.w * self.sub_image as i32;
-        self.apply_texture(clip, canvas);
+        self.apply_texture(image, clip, canvas);
+    }
+
+    /// Check object collisions, the bounding boxes first and then the
+    /// collider of each object
+    pub fn is_collision<R>(&self, rhs: &Object<R>, mask: &Mask) -> bool {
+        let (right, bottom) = self.corner();
+        let (rhs_right, rhs_bottom) = rhs.corner();
+
+        if right < rhs.pos.0
+            || bottom < rhs.pos.1
+            || rhs_right < self.pos.0
+            || rhs_bottom < self.pos.1
+        {
+            return false;
+        }
+
+        match (self.collider, rhs.collider) {
+            (Collider::Box, Collider::Box) => true,
+            (Collider::Circle, Collider::Circle) => {
+                let ((x, y, r), (x1, y1, r1)) = (self.circle(), rhs.circle());
+                (x1 - x).hypot(y1 - y) <= r + r1
+            }
+            (Collider::Box, Collider::Circle) => self.touches_circle(rhs.circle()),
+            (Collider::Circle, Collider::Box) => rhs.touches_circle(self.circle()),
+            // Look for a pixel covered by both where the boxes overlap
+            _ => {
+                let left = self.pos.0.max(rhs.pos.0).floor() as i32;
+                let top = self.pos.1.max(rhs.pos.1).floor() as i32;
+                let right = right.min(rhs_right).ceil() as i32;
+                let bottom = bottom.min(rhs_bottom).ceil() as i32;
+
+                (top..bottom).any(|y| {
+                    (left..right).any(|x| self.covers((x, y), mask) && rhs.covers((x, y), mask))
+                })
+            }
+        }
+    }
+
+    /// Bottom right corner of the bounding box
+    pub(crate) fn corner(&self) -> (f32, f32) {
+        (
+            self.pos.0 + self.clip.w as f32 * self.scale,
+            self.pos.1 + self.clip.h as f32 * self.scale,
+        )
+    }
+
+    /// Center and radius of the circle inscribed in the bounding box
+    fn circle(&self) -> (f32, f32, f32) {
+        let (w, h) = (
+            self.clip.w as f32 * self.scale,
+            self.clip.h as f32 * self.scale,
+        );
+        (self.pos.0 + w / 2.0, self.pos.1 + h / 2.0, w.min(h) / 2.0)
+    }
+
+    /// Check if the bounding box touches a circle
+    fn touches_circle(&self, (x, y, r): (f32, f32, f32)) -> bool {
+        let (right, bottom) = self.corner();
+        let closest = (x.clamp(self.pos.0, right), y.clamp(self.pos.1, bottom));
+
+        (closest.0 - x).hypot(closest.1 - y) <= r
     }
 
-    /// Check object collisions
-    pub fn is_collision<'rhs, R>(&self, rhs: &Object<'rhs, R>) -> bool {
-        self.pos.0 as f32 + self.clip.w as f32 * self.scale >= rhs.pos.0 as f32
-            && self.pos.1 as f32 + self.clip.h as f32 * self.scale >= rhs.pos.1 as f32
-            && rhs.pos.0 as f32 + rhs.clip.w as f32 * rhs.scale >= self.pos.0 as f32
-            && rhs.pos.1 as f32 + rhs.clip.h as f32 * rhs.scale >= self.pos.1 as f32
+    /// Check if a pixel of the screen inside the bounding box is covered by
+    /// the collider
+    fn covers(&self, (x, y): (i32, i32), mask: &Mask) -> bool {
+        match self.collider {
+            Collider::Box => true,
+            Collider::Circle => {
+                let (cx, cy, r) = self.circle();
+                (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy) <= r
+            }
+            Collider::Pixel => {
+                let dx = ((x as f32 + 0.5 - self.pos.0) / self.scale).floor() as i32;
+                let dy = ((y as f32 + 0.5 - self.pos.1) / self.scale).floor() as i32;
+                let clip = self.clip;
+
+                (0..clip.w).contains(&dx)
+                    && (0..clip.h).contains(&dy)
+                    && mask.is_opaque((clip.x + clip.w * self.sub_image as i32 + dx, clip.y + dy))
+            }
+        }
     }
 
     fn apply_texture_ex(
         &self,
+        image: &Texture,
         clip: Rect,
         angle: f64,
         center: Option<Point>,
         flip: (bool, bool),
         canvas: &mut WindowCanvas,
     ) {
-        let mut offset = clip;
-
-        offset.x = self.pos.0;
-        offset.y = self.pos.1;
-        offset.w *= self.scale as i32;
-        offset.h *= self.scale as i32;
+        let offset = FRect::new(
+            self.pos.0,
+            self.pos.1,
+            clip.w as f32 * self.scale,
+            clip.h as f32 * self.scale,
+        );
 
         let src: Option<FRect> = clip.into();
-        let dst: Option<FRect> = offset.into();
+        let dst = Some(offset);
         let center: Option<FPoint> = center.map(Into::into);
 
         canvas
-            .copy_ex(self.image(), src, dst, angle, center, flip.0, flip.1)
+            .copy_ex(image, src, dst, angle, center, flip.0, flip.1)
             .expect("Could not blit texture to render target!");
     }
 
-    pub fn apply_texture(&self, clip: Rect, canvas: &mut WindowCanvas) {
-        self.apply_texture_ex(clip, 0.0, None, (false, false), canvas);
+    pub fn apply_texture(&self, image: &Texture, clip: Rect, canvas: &mut WindowCanvas) {
+        self.apply_texture_ex(image, clip, 0.0, None, (false, false), canvas);
     }
 }
 
-impl<'tex, T: Sized> Deref for Object<'tex, T> {
+impl<T: Sized> Deref for Object<T> {
     type Target = T;
 
     #[inline(always)]
@@ -123,116 +217,118 @@ impl<'tex, T: Sized> Deref for Object<'tex, T> {
     }
 }
 
-impl<'tex, T: Sized> DerefMut for Object<'tex, T> {
+impl<T: Sized> DerefMut for Object<T> {
     #[inline(always)]
     fn deref_mut(&mut self) -> &mut T {
         &mut self.this
     }
 }
 
-fn get_x(chr: &u8) -> i32 {
-    match *chr as i32 {
-        c if c <= 43 => c - 32,
-        c if c <= 55 => c - 44,
-        c if c <= 67 => c - 56,
-        c if c <= 79 => c - 68,
-        c if c <= 91 => c - 80,
-        c if c <= 103 => c - 92,
-        c if c <= 115 => c - 104,
-        c => c - 116,
-    }
-}
-
-fn get_y(c: &u8) -> i32 {
-    match *c as i32 {
-        c if c <= 43 => 0,
-        c if c <= 55 => 1,
-        c if c <= 67 => 2,
-        c if c <= 79 => 3,
-        c if c <= 91 => 4,
-        c if c <= 103 => 5,
-        c if c <= 115 => 6,
-        _ => 7,
-    }
+pub struct Asteroid {
+    pub(crate) lives: i16,
+    pub(crate) kind: Size,
+    /// Movement per second, x and y
+    pub(crate) velocity: (f32, f32),
 }
 
-pub struct Text<'tex> {
-    chucks: Vec<Object<'tex, ()>>,
-}
+/// Angle between the fragments of a destroyed asteroid, in radians
+const FRAGMENT_SPREAD: f32 = 0.6;
 
-impl<'tex> Text<'tex> {
-    pub fn new(image: &'tex Texture, text: &str, size: Size, scale: f32) -> Self {
-        let ([width, height], [w, h]) = ([size as i32; 2], [size as u32; 2]);
-        let mut chucks = Vec::new();
-
-        for chr in text.as_bytes() {
-            chucks.push(Object::new(
-                (),
-                image,
-                0,
-                Rect::new(get_x(chr) * width, get_y(chr) * height, w, h),
-                scale,
-            ));
-        }
+impl Object<Asteroid> {
+    /// Create an asteroid of the given size, position and velocity
+    pub(crate) fn asteroid(kind: Size, pos: (f32, f32), velocity: (f32, f32)) -> Self {
+        let (clip, lives) = match kind {
+            Size::Small => (Rect::new(0, 32, 32, 32), 1),
+            Size::Medium => (Rect::new(32, 32, 64, 64), 3),
+            _ => (Rect::new(96, 32, 96, 96), 6),
+        };
 
-        Text { chucks }
+        let mut obj = Object::new(
+            Asteroid {
+                lives,
+                kind,
+                velocity,
+            },
+            0,
+            clip,
+            1.0,
+        );
+        obj.pos = pos;
+        obj.collider = Collider::Circle;
+        obj
     }
 
-    pub fn position(&mut self, (x, y): (i32, i32), canvas: &mut WindowCanvas) {
-        let (mut i, x2) = (0.0f32, x as f32);
-
-        for obj in &mut self.chucks {
-            let mut clip = obj.clip();
-            obj.pos.0 = (x2 + ((obj.clip().width() as f32 * 0.5 * obj.scale) * i)) as i32;
-            i += 1.0;
-            obj.pos.1 = y;
-            clip.x += clip.w * obj.sub_image() as i32;
-            obj.apply_texture(clip, canvas);
-        }
-    }
+    /// Break the asteroid into smaller ones flying apart from its center
+    pub(crate) fn fragments(&self, rng: &mut GameRng) -> Vec<Self> {
+        let (kind, count) = match self.kind {
+            Size::Large => (Size::Medium, 2),
+            Size::Medium => (Size::Small, 3),
+            _ => return Vec::new(),
+        };
 
-    pub fn chucks(&self) -> &[Object<'tex, ()>] {
-        &self.chucks
+        let center = self.center();
+        let (vx, vy) = self.velocity;
+
+        (0..count)
+            .map(|i| {
+                // Rotate the parent velocity to fan the fragments out
+                let jitter = (rng.rand() % 21 - 10) as f32 / 100.0;
+                let angle = (i as f32 - (count - 1) as f32 / 2.0) * FRAGMENT_SPREAD + jitter;
+                let (sin, cos) = angle.sin_cos();
+                let velocity = ((vx * cos - vy * sin) * 1.25, (vx * sin + vy * cos) * 1.25);
+
+                let mut obj = Object::asteroid(kind, (0.0, 0.0), velocity);
+                obj.center_on(center);
+                obj
+            })
+            .collect()
     }
 }
 
-pub struct Asteroid {
-    pub(crate) lives: i16,
-    pub(crate) kind: Size,
-}
-
-pub struct Asteroids<'tex> {
-    image: &'tex Texture,
-    pub(crate) objs: Vec<Object<'tex, Asteroid>>,
+#[derive(Default)]
+pub struct Asteroids {
+    pub(crate) objs: Vec<Object<Asteroid>>,
 }
 
-impl<'tex> Asteroids<'tex> {
-    pub fn new(image: &'tex Texture) -> Self {
-        Asteroids {
-            image,
-            objs: Vec::new(),
-        }
+impl Asteroids {
+    pub fn new() -> Self {
+        Asteroids { objs: Vec::new() }
     }
 
-    pub fn update(&mut self, screen: Screen, speed: &mut Speed, canvas: &mut WindowCanvas) {
+    /// Spawn and move the asteroids for one simulation step, speeding them
+    /// up by the given factor per second
+    pub fn update(
+        &mut self,
+        wave: &mut Wave,
+        screen: Screen,
+        speed: &mut Speed,
+        acceleration: f32,
+        rng: &mut GameRng,
+    ) {
         let Speed { asteroid, ship, .. } = speed;
         *asteroid *= if *asteroid < *ship * 2.0 {
-            1.00025
+            acceleration.powf(STEP)
         } else {
             1.0
         };
 
-        let val = if *asteroid < 30.0 { *asteroid } else { 30.0 };
+        // Roll against the number of asteroids expected during this step
+        let odds = (wave.rate * STEP * 1000.0) as i32;
 
-        if rand() % val as i32 == 0 {
-            let (clip, lives, kind) = match rand() % 6 {
-                random if random >= 3 => (Rect::new(0, 32, 32, 32), 1, Size::Small),
-                random if random >= 1 => (Rect::new(32, 32, 64, 64), 3, Size::Medium),
-                _ => (Rect::new(96, 32, 96, 96), 6, Size::Large),
-            };
+        if !wave.is_spawned() && rng.rand() % 1000 < odds {
+            wave.budget -= 1;
 
-            let mut obj = Object::new(Asteroid { lives, kind }, self.image, 0, clip, 1.0);
-            obj.pos = ((rand() % screen.width) - (clip.w / 2), -clip.h);
+            // Fall with a slight horizontal drift
+            let drift = (rng.rand() % 41 - 20) as f32 / 100.0 * speed.asteroid;
+            let mut obj = Object::asteroid(
+                wave.size(rng.rand() as u32),
+                (0.0, 0.0),
+                (drift, speed.asteroid),
+            );
+            obj.pos = (
+                ((rng.rand() % screen.width) - (obj.clip.w / 2)) as f32,
+                -obj.clip.h as f32,
+            );
             self.objs.push(obj);
         }
 
@@ -240,120 +336,193 @@ impl<'tex> Asteroids<'tex> {
 
         while self.objs.len() > pos {
             let this = &mut self.objs[pos];
-            if this.pos.1 > screen.height + this.clip.h || this.lives <= 0 {
+            if this.pos.1 > (screen.height + this.clip.h) as f32
+                || this.pos.1 < -this.clip.h as f32
+                || this.pos.0 > screen.width as f32
+                || this.pos.0 < -this.clip.w as f32
+                || this.lives <= 0
+            {
                 self.objs.remove(pos);
                 continue;
             }
 
-            this.position((0, speed.asteroid as _), canvas);
+            let velocity = this.velocity;
+            this.advance(velocity);
 
             pos += 1;
         }
     }
-}
-
-trait Pressed<T> {
-    fn is_pressed(&self, code: T) -> bool;
-}
-
-impl Pressed<Scancode> for EventPump {
-    fn is_pressed(&self, code: Scancode) -> bool {
-        self.keyboard_state().is_scancode_pressed(code)
-    }
-}
 
-impl Pressed<[Scancode; 2]> for EventPump {
-    fn is_pressed(&self, [code, code1]: [Scancode; 2]) -> bool {
-        self.is_pressed(code) || self.is_pressed(code1)
+    pub fn draw(&self, image: &Texture, canvas: &mut WindowCanvas) {
+        for obj in &self.objs {
+            obj.draw(image, canvas);
+        }
     }
 }
 
 pub(crate) struct Bullet {
     pub(crate) lives: i16,
+    /// Sideways movement per second
+    drift: f32,
 }
 
-pub(crate) struct Ship<'tex> {
+pub(crate) struct Ship {
     pub(crate) lives: i16,
-    pub(crate) bullets: Vec<Object<'tex, Bullet>>,
+    pub(crate) score: u32,
+    pub(crate) bullets: Vec<Object<Bullet>>,
+    /// Absorbs the next hit
+    pub(crate) shield: bool,
+    /// Time at which the spread shot wears off
+    pub(crate) spread: f64,
+    /// Time at which the rapid fire wears off
+    pub(crate) rapid: f64,
+    /// Time at which the ship can fire again
+    pub(crate) reload: f64,
 }
 
-impl<'tex> Object<'tex, Ship<'tex>> {
-    pub(crate) fn update(&mut self, (screen, speed, timer, events, canvas): ScreenItems) {
-        let (mut ship_x, mut ship_y) = (0i8, 0i8);
-        let mut temp: i32;
-
-        /* User Keyboard  */
-        if events.is_pressed([Scancode::Left, Scancode::A]) {
-            ship_x -= 1;
-        }
+impl Ship {
+    /// Check if the player still has lives left
+    pub(crate) fn is_playing(&self) -> bool {
+        self.lives > 0
+    }
+}
 
-        if events.is_pressed([Scancode::Right, Scancode::D]) {
-            ship_x += 1;
-        }
+/// Points earned by every player together
+pub(crate) fn total_score(ships: &[Object<Ship>]) -> u32 {
+    ships.iter().map(|ship| ship.score).sum()
+}
 
-        if events.is_pressed([Scancode::Up, Scancode::W]) {
-            ship_y -= 1;
-        }
+impl Object<Ship> {
+    /// Create the ship of the given player, the ships are spread evenly
+    /// across the middle of the screen
+    pub(crate) fn spawn(lives: i16, screen: Screen, player: usize, players: usize) -> Self {
+        let mut ship = Object::new(
+            Ship {
+                lives,
+                score: 0,
+                bullets: Vec::new(),
+                shield: false,
+                spread: 0.0,
+                rapid: 0.0,
+                reload: 0.0,
+            },
+            0,
+            Rect::new(0, 0, 32, 32),
+            1.0,
+        );
+
+        ship.pos = (
+            (screen.width * (player as i32 + 1) / (players as i32 + 1)) as f32,
+            (screen.height / 2) as f32,
+        );
+        ship.collider = Collider::Pixel;
+        ship
+    }
 
-        if events.is_pressed([Scancode::Down, Scancode::S]) {
-            ship_y += 1;
-        }
+    /// Move the ship and its bullets, returning true if a bullet was fired
+    pub(crate) fn update(&mut self, (screen, speed, timer, input, config): ScreenItems) -> bool {
+        let (stick_x, stick_y) = input.stick;
 
         /* Updating Ship Animation */
-        if ship_x == 0 {
-            self.sub_image = 0;
-        } else if ship_x == -1 {
-            self.sub_image = 1;
-        } else if ship_x == 1 {
-            self.sub_image = 2;
-        }
+        self.sub_image = match stick_x {
+            0 => 0,
+            x if x < 0 => 1,
+            _ => 2,
+        };
 
-        ship_x *= speed.ship as i8;
-        ship_y *= speed.ship as i8;
+        /* Stick movement, partial for analog sticks */
+        let velocity = speed.ship / f32::from(FULL);
+        self.advance((velocity * f32::from(stick_x), velocity * f32::from(stick_y)));
 
         /* Setting Ship Boundaries */
-        if (self.pos.0 + ship_x as i32) < screen.left as i32 {
-            temp = screen.left as i32 - self.pos.0;
-            ship_x = if temp > 0 { temp as _ } else { 0 };
-        }
+        let right = screen.width as f32 - screen.right - self.clip.w as f32;
+        let bottom = screen.height as f32 - screen.bottom - self.clip.h as f32;
+        self.pos.0 = self.pos.0.min(right).max(screen.left);
+        self.pos.1 = self.pos.1.min(bottom).max(screen.top);
+
+        self.update_bullets((screen, speed, timer, input, config))
+    }
 
-        if self.pos.0 + self.clip.w + ship_x as i32 > screen.width - screen.right as i32 {
-            temp = (screen.width - screen.right as i32) - self.pos.0 - self.clip.w;
-            ship_x = if temp > 0 { temp as _ } else { 0 };
+    /// Give the ship the effect of a collected power-up
+    pub(crate) fn power_up(&mut self, power: PowerUp, now: f64) {
+        match power {
+            PowerUp::Shield => self.shield = true,
+            PowerUp::Spread => self.spread = now + DURATION,
+            PowerUp::Rapid => self.rapid = now + DURATION,
+            PowerUp::Life => self.lives += 1,
         }
+    }
 
-        if (self.pos.1 + ship_y as i32) < screen.top as i32 {
-            temp = screen.top as i32 - self.pos.1;
-            ship_y = if temp > 0 { temp as _ } else { 0 };
+    /// Draw the ship and its bullets in the tint of their player, nothing
+    /// is left once the player is out
+    pub(crate) fn render(&self, player: usize, image: &mut Texture, canvas: &mut WindowCanvas) {
+        if !self.is_playing() {
+            return;
         }
 
-        if self.pos.1 + self.clip.h + ship_y as i32 > screen.height - screen.bottom as i32 {
-            temp = (screen.height - screen.bottom as i32) - self.pos.1 - self.clip.h;
-            ship_y = if temp > 0 { temp as _ } else { 0 };
+        let (r, g, b) = PLAYER_TINTS[player % PLAYER_TINTS.len()];
+        image.set_color_mod(r, g, b);
+        self.draw(image, canvas);
+
+        if self.shield {
+            // Ring around the ship while the shield lasts
+            let radius = self.clip.w as f32 * 0.75;
+            let center = self.center();
+            let ring: Vec<FPoint> = (0..48)
+                .map(|i| {
+                    let (sin, cos) = (i as f32 * std::f32::consts::TAU / 48.0).sin_cos();
+                    FPoint::new(center.0 + radius * cos, center.1 + radius * sin)
+                })
+                .collect();
+
+            canvas.set_draw_color(Color::from((0, 200, 255, 255)));
+            canvas.draw_points(&ring[..]).unwrap();
+            canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
         }
 
-        self.position((ship_x as _, ship_y as _), canvas);
-        self.update_bullets((screen, speed, timer, events, canvas));
+        for bullet in &self.bullets {
+            bullet.draw(image, canvas);
+        }
+        image.set_color_mod(0xff, 0xff, 0xff);
     }
 
-    fn update_bullets(&mut self, (screen, speed, timer, events, canvas): ScreenItems) {
-        let now = sdl3::timer::ticks();
-        if timer.bullet < now && events.is_pressed([Scancode::_1, Scancode::Space]) {
-            let mut bullet = Object::new(
-                Bullet { lives: 1 },
-                self.image(),
-                0,
-                Rect::new(0, 144, 16, 16),
-                1.0,
-            );
+    fn update_bullets(&mut self, (screen, speed, timer, input, config): ScreenItems) -> bool {
+        let now = timer.global;
+        let fired = self.reload <= now && input.fire;
 
-            bullet.pos = (
-                self.pos.0 + (bullet.clip.w / 2),
-                self.pos.1 - (bullet.clip.w / 2),
-            );
+        if fired {
+            let drifts: &[f32] = if self.spread > now {
+                &[-SPREAD_DRIFT, 0.0, SPREAD_DRIFT]
+            } else {
+                &[0.0]
+            };
+
+            for &drift in drifts {
+                let mut bullet = Object::new(
+                    Bullet {
+                        lives: 1,
+                        drift: drift * speed.bullet,
+                    },
+                    0,
+                    Rect::new(0, 144, 16, 16),
+                    1.0,
+                );
+
+                bullet.collider = Collider::Pixel;
+                bullet.pos = (
+                    self.pos.0 + (bullet.clip.w / 2) as f32,
+                    self.pos.1 - (bullet.clip.w / 2) as f32,
+                );
+
+                self.bullets.push(bullet);
+            }
 
-            self.bullets.push(bullet);
-            timer.bullet = now + 150;
+            let cooldown = if self.rapid > now {
+                config.rapid_cooldown
+            } else {
+                config.bullet_cooldown
+            };
+            self.reload = now + cooldown;
         }
 
         let mut pos = 0;
@@ -361,14 +530,17 @@ impl<'tex> Object<'tex, Ship<'tex>> {
         while self.bullets.len() > pos {
             let this = &mut self.bullets[pos];
 
-            if this.pos.1 <= screen.top as i32 || this.lives <= 0 {
+            if this.pos.1 <= screen.top || this.lives <= 0 {
                 self.bullets.remove(pos);
                 continue;
             }
 
-            this.position((0, -(speed.bullet as i32)), canvas);
+            let drift = this.drift;
+            this.advance((drift, -speed.bullet));
 
             pos += 1;
         }
+
+        fired
     }
 }
@@ -0,0 +1,122 @@
+use crate::{global::STEP, object::Object};
+use rand::RngExt;
+use sdl3::{
+    rect::Rect,
+    render::{Texture, WindowCanvas},
+};
+
+/// Most particles alive at once, new ones are dropped past it
+const MAX_PARTICLES: usize = 256;
+
+/// Number of debris sprites in the first row of the sprite sheet
+const DEBRIS_SPRITES: i32 = 9;
+
+/// Time the screen shakes after the ship is hit, in seconds
+const SHAKE_TIME: f32 = 0.3;
+
+/// Farthest the screen moves while shaking, in pixels
+const SHAKE_STRENGTH: f32 = 8.0;
+
+/// Visual feedback requested by the simulation
+pub enum Effect {
+    /// Debris flying out of the given center
+    Blast { center: (f32, f32), count: usize },
+    /// The ship was hit
+    Shake,
+}
+
+pub struct Particle {
+    velocity: (f32, f32),
+    /// Time left before the particle disappears, in seconds
+    life: f32,
+    lifetime: f32,
+}
+
+/// Debris of destroyed objects and the shake of the screen, only drawn so
+/// they use their own random numbers and never touch the game ones
+#[derive(Default)]
+pub struct Particles {
+    objs: Vec<Object<Particle>>,
+    /// Time left of the screen shake, in seconds
+    shake: f32,
+}
+
+impl Particles {
+    pub fn new() -> Self {
+        Particles {
+            objs: Vec::new(),
+            shake: 0.0,
+        }
+    }
+
+    pub fn apply(&mut self, effect: Effect) {
+        match effect {
+            Effect::Blast { center, count } => self.blast(center, count),
+            Effect::Shake => self.shake = SHAKE_TIME,
+        }
+    }
+
+    fn blast(&mut self, center: (f32, f32), count: usize) {
+        let mut rng = rand::rng();
+        let count = count.min(MAX_PARTICLES - self.objs.len());
+
+        for _ in 0..count {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = rng.random_range(60.0..240.0);
+            let lifetime = rng.random_range(0.3..0.8);
+            let sprite = rng.random_range(0..DEBRIS_SPRITES);
+            let (sin, cos) = angle.sin_cos();
+
+            let mut obj = Object::new(
+                Particle {
+                    velocity: (cos * speed, sin * speed),
+                    life: lifetime,
+                    lifetime,
+                },
+                0,
+                Rect::new(96 + sprite * 32, 0, 32, 32),
+                1.0,
+            );
+
+            obj.center_on(center);
+            self.objs.push(obj);
+        }
+    }
+
+    /// Move the particles and wind down the shake for one simulation step
+    pub fn update(&mut self) {
+        self.shake = (self.shake - STEP).max(0.0);
+
+        self.objs.retain_mut(|obj| {
+            let velocity = obj.velocity;
+            obj.advance(velocity);
+            obj.life -= STEP;
+            obj.life > 0.0
+        });
+    }
+
+    /// Offset of the screen while it shakes, smaller as the shake ends
+    pub fn shake_offset(&self) -> (i32, i32) {
+        if self.shake <= 0.0 {
+            return (0, 0);
+        }
+
+        let strength = SHAKE_STRENGTH * self.shake / SHAKE_TIME;
+        let mut rng = rand::rng();
+
+        (
+            (rng.random_range(-1.0..1.0) * strength) as i32,
+            (rng.random_range(-1.0..1.0) * strength) as i32,
+        )
+    }
+
+    /// Draw the particles fading out over their lifetime
+    pub fn draw(&self, image: &mut Texture, canvas: &mut WindowCanvas) {
+        for obj in &self.objs {
+            image.set_alpha_mod((255.0 * obj.life / obj.lifetime) as u8);
+            obj.draw(image, canvas);
+        }
+
+        image.set_alpha_mod(255);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    collision::{Collider, Mask},
+    global::{GameRng, Screen},
+    object::{Object, Ship},
+};
+use sdl3::{
+    rect::Rect,
+    render::{Texture, WindowCanvas},
+};
+
+/// Falling speed of the power-ups, in pixels per second
+const FALL_SPEED: f32 = 120.0;
+
+/// Time the spread shot and the rapid fire last, in seconds
+pub const DURATION: f64 = 10.0;
+
+/// Effects that can be collected by the ship
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum PowerUp {
+    /// Absorbs the next hit
+    Shield,
+    /// Fires three bullets at once
+    Spread,
+    /// Shortens the time between shots
+    Rapid,
+    /// Adds a life
+    Life,
+}
+
+impl PowerUp {
+    const ALL: [PowerUp; 4] = [
+        PowerUp::Shield,
+        PowerUp::Spread,
+        PowerUp::Rapid,
+        PowerUp::Life,
+    ];
+
+    /// Pick the power-up dropped by a destroyed asteroid, if any, with the
+    /// given chance out of 100
+    pub fn roll(chance: i32, rng: &mut GameRng) -> Option<PowerUp> {
+        (rng.rand() % 100 < chance).then(|| PowerUp::ALL[rng.rand() as usize % 4])
+    }
+
+    /// Icon of the power-up in the sprite sheet
+    pub fn clip(self) -> Rect {
+        Rect::new(self as i32 * 16, 160, 16, 16)
+    }
+}
+
+/// A power-up falling down the screen
+pub struct Pickup {
+    pub(crate) kind: PowerUp,
+}
+
+#[derive(Default)]
+pub struct PowerUps {
+    pub(crate) objs: Vec<Object<Pickup>>,
+}
+
+impl PowerUps {
+    pub fn new() -> Self {
+        PowerUps { objs: Vec::new() }
+    }
+
+    /// Drop a power-up centered on the given point
+    pub fn spawn(&mut self, kind: PowerUp, center: (f32, f32)) {
+        let mut obj = Object::new(Pickup { kind }, 0, kind.clip(), 1.0);
+
+        obj.center_on(center);
+        obj.collider = Collider::Circle;
+        self.objs.push(obj);
+    }
+
+    /// Move the power-ups for one simulation step and return the ones
+    /// collected, with the index of the ship that got each of them
+    pub fn update(
+        &mut self,
+        ships: &[Object<Ship>],
+        screen: Screen,
+        mask: &Mask,
+    ) -> Vec<(usize, PowerUp)> {
+        let mut collected = Vec::new();
+
+        self.objs.retain_mut(|obj| {
+            let collector = ships
+                .iter()
+                .position(|ship| ship.is_playing() && obj.is_collision(ship, mask));
+
+            if let Some(player) = collector {
+                collected.push((player, obj.kind));
+                return false;
+            }
+
+            obj.advance((0.0, FALL_SPEED));
+            obj.pos.1 <= screen.height as f32
+        });
+
+        collected
+    }
+
+    pub fn draw(&self, image: &Texture, canvas: &mut WindowCanvas) {
+        for obj in &self.objs {
+            obj.draw(image, canvas);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{
+    config::Config,
+    global::{Difficulty, Options},
+    input::Input,
+};
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Error, ErrorKind},
+    path::Path,
+};
+
+/// First line of every replay file
+const HEADER: &str = "asterlike-replay 4";
+
+/// Everything needed to simulate a game again exactly: its seed, its
+/// settings and the input of every player at each simulation step
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    /// Screen size, which bounds the ships and the asteroid spawns
+    pub screen: (u32, u32),
+    pub players: usize,
+    /// Game balance the game was played with, the video settings are left
+    /// to their defaults
+    pub config: Config,
+    pub inputs: Vec<Vec<Input>>,
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        difficulty: Difficulty,
+        screen: (u32, u32),
+        players: usize,
+        config: Config,
+    ) -> Self {
+        Replay {
+            seed,
+            difficulty,
+            screen,
+            players,
+            config,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Write the replay as text: a header, the settings, then one step per
+    /// line with the input of each player
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let balance = self.config.balance();
+        let mut data = format!(
+            "{HEADER}\nseed {}\ndifficulty {}\nscreen {} {}\nplayers {}\nconfig {}\n{balance}\n",
+            self.seed,
+            self.difficulty.name(),
+            self.screen.0,
+            self.screen.1,
+            self.players,
+            balance.lines().count()
+        );
+
+        for inputs in &self.inputs {
+            let line: Vec<String> = inputs
+                .iter()
+                .map(|input| format!("{:x}", input.to_bits()))
+                .collect();
+            writeln!(data, "{}", line.join(" ")).unwrap();
+        }
+
+        fs::write(path, data)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let data = fs::read_to_string(path)?;
+        let invalid = |what: &str| Error::new(ErrorKind::InvalidData, format!("invalid {what}"));
+        let mut lines = data.lines();
+
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("header"));
+        }
+
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .map(str::trim)
+                .ok_or_else(|| invalid(name))
+        };
+
+        let seed = field("seed")?.parse().map_err(|_| invalid("seed"))?;
+        let difficulty =
+            Difficulty::from_name(field("difficulty")?).ok_or_else(|| invalid("difficulty"))?;
+        // The screen size must fit the i32 coordinates of the simulation
+        let size = |value: &str| {
+            value
+                .parse()
+                .ok()
+                .filter(|size| (1..=i32::MAX as u32).contains(size))
+        };
+        let screen = field("screen")?
+            .split_once(' ')
+            .and_then(|(w, h)| Some((size(w)?, size(h)?)))
+            .ok_or_else(|| invalid("screen"))?;
+        let players = field("players")?
+            .parse()
+            .ok()
+            .filter(|players| (1..=Options::MAX_PLAYERS).contains(players))
+            .ok_or_else(|| invalid("players"))?;
+        let settings: usize = field("config")?.parse().map_err(|_| invalid("config"))?;
+
+        let mut config = Config::default();
+        let balance: Vec<&str> = lines.by_ref().take(settings).collect();
+        if balance.len() != settings {
+            return Err(invalid("config"));
+        }
+        config.parse(&balance.join("\n"))?;
+
+        let inputs = lines
+            .map(|line| {
+                let inputs = line
+                    .split_whitespace()
+                    .map(|bits| u32::from_str_radix(bits, 16).ok().map(Input::from_bits))
+                    .collect::<Option<Vec<_>>>()?;
+                (inputs.len() == players).then_some(inputs)
+            })
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("inputs"))?;
+
+        Ok(Replay {
+            seed,
+            difficulty,
+            screen,
+            players,
+            config,
+            inputs,
+        })
+    }
+}
@@ -0,0 +1,96 @@
+/// File holding the high-score table on native builds
+#[cfg(not(target_os = "emscripten"))]
+const SCORES_PATH: &str = "highscores.txt";
+
+/// Number of letters in the initials of an entry
+pub const INITIALS: usize = 3;
+
+pub struct Entry {
+    pub initials: String,
+    pub score: u32,
+}
+
+/// Best scores, highest first
+pub struct HighScores {
+    entries: Vec<Entry>,
+}
+
+impl HighScores {
+    pub const MAX: usize = 10;
+
+    /// Load the table saved on disk, or an empty one
+    #[cfg(not(target_os = "emscripten"))]
+    pub fn load() -> Self {
+        let data = std::fs::read_to_string(SCORES_PATH).unwrap_or_default();
+        let mut scores = HighScores::parse(&data);
+        scores
+            .entries
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        scores.entries.truncate(HighScores::MAX);
+        scores
+    }
+
+    /// The web build has no disk, scores last until the page is closed
+    #[cfg(target_os = "emscripten")]
+    pub fn load() -> Self {
+        HighScores {
+            entries: Vec::new(),
+        }
+    }
+
+    #[cfg(not(target_os = "emscripten"))]
+    pub fn save(&self) {
+        let data: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{} {}\n", entry.initials, entry.score))
+            .collect();
+
+        if let Err(err) = std::fs::write(SCORES_PATH, data) {
+            eprintln!("unable to save high scores: {err}");
+        }
+    }
+
+    #[cfg(target_os = "emscripten")]
+    pub fn save(&self) {}
+
+    /// Read lines of initials and score, skipping malformed ones
+    #[cfg(not(target_os = "emscripten"))]
+    fn parse(data: &str) -> Self {
+        let entries = data
+            .lines()
+            .filter_map(|line| {
+                let (initials, score) = line.trim().split_once(' ')?;
+                Some(Entry {
+                    initials: initials.to_string(),
+                    score: score.trim().parse().ok()?,
+                })
+            })
+            .collect();
+
+        HighScores { entries }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Check if a score is good enough to enter the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < HighScores::MAX
+                || self.entries.iter().any(|entry| entry.score < score))
+    }
+
+    pub fn insert(&mut self, initials: &str, score: u32) {
+        let index = self.entries.partition_point(|entry| entry.score >= score);
+        self.entries.insert(
+            index,
+            Entry {
+                initials: initials.to_string(),
+                score,
+            },
+        );
+        self.entries.truncate(HighScores::MAX);
+    }
+}
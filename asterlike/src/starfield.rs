@@ -0,0 +1,70 @@
+use crate::global::{STEP, Screen};
+use rand::RngExt;
+use sdl3::{pixels::Color, render::FPoint, render::WindowCanvas};
+
+/// Speed of each layer relative to the asteroids, its number of stars for
+/// every 10000 square pixels and its brightness, from the farthest one
+const LAYERS: [(f32, f32, u8); 3] = [(0.15, 1.2, 90), (0.35, 0.6, 160), (0.7, 0.25, 255)];
+
+struct Layer {
+    stars: Vec<(f32, f32)>,
+    factor: f32,
+    color: Color,
+}
+
+/// Procedural background of stars scrolling down, the nearer layers faster
+pub struct Starfield {
+    layers: Vec<Layer>,
+    height: f32,
+}
+
+impl Starfield {
+    /// Scatter stars over the whole screen
+    pub fn new(screen: Screen) -> Self {
+        let mut rng = rand::rng();
+        let (width, height) = (screen.width.max(1) as f32, screen.height.max(1) as f32);
+
+        let layers = LAYERS
+            .iter()
+            .map(|&(factor, density, light)| {
+                let count = (width * height / 10000.0 * density) as usize;
+
+                Layer {
+                    stars: (0..count)
+                        .map(|_| (rng.random_range(0.0..width), rng.random_range(0.0..height)))
+                        .collect(),
+                    factor,
+                    color: Color::from((light, light, light, 255)),
+                }
+            })
+            .collect();
+
+        Starfield { layers, height }
+    }
+
+    /// Scroll the stars for one simulation step, wrapping them at the bottom
+    pub fn update(&mut self, asteroid_speed: f32) {
+        for layer in &mut self.layers {
+            let dy = asteroid_speed * layer.factor * STEP;
+
+            for star in &mut layer.stars {
+                star.1 = (star.1 + dy) % self.height;
+            }
+        }
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas) {
+        for layer in &self.layers {
+            let points: Vec<FPoint> = layer
+                .stars
+                .iter()
+                .map(|&(x, y)| FPoint::new(x, y))
+                .collect();
+
+            canvas.set_draw_color(layer.color);
+            canvas.draw_points(&points[..]).unwrap();
+        }
+
+        canvas.set_draw_color(Color::from((0x0, 0x0, 0x0, 255)));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{config::Config, object::Size};
+
+/// Time the "Wave N" message stays on screen, in seconds
+pub const WAVE_DELAY: f64 = 2.0;
+
+/// First wave with enemy ships
+const ENEMY_WAVE: u32 = 3;
+
+/// Definition of a wave of asteroids
+pub struct Wave {
+    pub number: u32,
+    /// Asteroids left to spawn
+    pub budget: u32,
+    /// Odds of spawning small, medium and large asteroids
+    pub mix: [u32; 3],
+    /// Factor applied to the asteroid speed
+    pub speed: f32,
+    /// Asteroids spawned per second on average
+    pub rate: f32,
+    /// Enemy ships left to spawn
+    pub enemies: u32,
+    /// Enemy ships spawned per second on average
+    pub enemy_rate: f32,
+}
+
+impl Wave {
+    /// Scale the spawn rates of the config to the given wave
+    pub fn new(number: u32, config: &Config) -> Self {
+        let n = number.saturating_sub(1);
+
+        Wave {
+            number,
+            budget: 10 + 5 * n,
+            mix: [4, 2 + (n / 2).min(2), n.min(3)],
+            speed: (1.0 + 0.1 * n as f32).min(2.0),
+            rate: (1.0 + 0.25 * n as f32).min(4.0) * config.asteroid_rate,
+            enemies: (number + 1).saturating_sub(ENEMY_WAVE).min(8),
+            enemy_rate: config.enemy_rate,
+        }
+    }
+
+    /// Make the wave faster and longer for each boss defeated
+    pub fn harden(mut self, bosses: u32) -> Self {
+        let factor = 1.0 + 0.2 * bosses as f32;
+
+        self.speed *= factor;
+        self.rate *= factor;
+        self.enemy_rate *= factor;
+        self.budget += 5 * bosses;
+        self.enemies += bosses;
+        self
+    }
+
+    /// Check if every asteroid of the wave has been spawned
+    pub fn is_spawned(&self) -> bool {
+        self.budget == 0
+    }
+
+    /// Pick the size of an asteroid from a random roll
+    pub fn size(&self, roll: u32) -> Size {
+        let [small, medium, large] = self.mix;
+
+        match roll % (small + medium + large) {
+            r if r < small => Size::Small,
+            r if r < small + medium => Size::Medium,
+            _ => Size::Large,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// This file is part of sokoban-rs
+// Copyright 2015 Sébastien Watteau
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::game::Level;
+
+/// Represents the part of the level that is visible on the screen.
+///
+/// The zoom is relative to the size at which the whole level fits onto the
+/// screen, and the center is expressed in columns and rows of the level so
+/// that it does not depend on the tileset being used.
+pub struct Camera {
+    /// The zoom factor
+    zoom: f64,
+    /// The column and row at the center of the screen
+    center: (f64, f64),
+    /// Whether the camera keeps the player at the center
+    follow: bool,
+}
+
+impl Camera {
+    /// The smallest zoom factor, showing the whole level.
+    pub const MIN_ZOOM: f64 = 1.0;
+    /// The largest zoom factor.
+    pub const MAX_ZOOM: f64 = 8.0;
+    /// The factor applied on each zoom step.
+    const ZOOM_STEP: f64 = 1.25;
+
+    /// Creates a new instance showing the whole level.
+    pub fn new() -> Camera {
+        Camera {
+            zoom: Camera::MIN_ZOOM,
+            center: (0.0, 0.0),
+            follow: true,
+        }
+    }
+
+    /// Resets the zoom and makes the camera follow the player again.
+    pub fn reset(&mut self, level: &Level) {
+        self.zoom = Camera::MIN_ZOOM;
+        self.follow(level);
+    }
+
+    /// Returns the zoom factor.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Returns the column and row at the center of the screen.
+    pub fn center(&self) -> (f64, f64) {
+        self.center
+    }
+
+    /// Zooms in by the given number of steps, or out if it is negative.
+    pub fn zoom_by(&mut self, steps: i32) {
+        let zoom = self.zoom * Camera::ZOOM_STEP.powi(steps);
+        self.zoom = zoom.clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM);
+    }
+
+    /// Moves the center by the given number of columns and rows, and stops
+    /// following the player.
+    pub fn pan(&mut self, level: &Level, cols: f64, rows: f64) {
+        self.follow = false;
+        self.center = (self.center.0 + cols, self.center.1 + rows);
+        self.clamp(level);
+    }
+
+    /// Centers the camera on the player and keeps following them.
+    pub fn follow(&mut self, level: &Level) {
+        self.follow = true;
+        self.update(level);
+    }
+
+    /// Updates the center of the camera after the player moved.
+    pub fn update(&mut self, level: &Level) {
+        if self.follow {
+            let player = level.player();
+            let shift = level.rules().topology().column_offset(player.row());
+            self.center = (
+                f64::from(player.column()) + shift + 0.5,
+                f64::from(player.row()) + 0.5,
+            );
+        }
+        self.clamp(level);
+    }
+
+    /// Keeps the center inside the level.
+    fn clamp(&mut self, level: &Level) {
+        let (cols, rows) = level.extents();
+        self.center = (
+            self.center.0.clamp(0.0, f64::from(cols) + 0.5),
+            self.center.1.clamp(0.0, f64::from(rows)),
+        );
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera::new()
+    }
+}
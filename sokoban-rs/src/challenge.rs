@@ -0,0 +1,211 @@
+// This file is part of sokoban-rs
+// Copyright 2015 Sébastien Watteau
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Optional challenges: a countdown per level, a limit on the number of moves
+//! and the scoring of a whole collection.
+
+use crate::game::Level;
+use std::time::{Duration, Instant};
+
+/// Represents the state of the level being played.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The level is being played
+    Playing,
+    /// All the boxes are on the squares
+    Completed,
+    /// The countdown reached zero
+    TimeUp,
+    /// The player used all the moves allowed by the par of the level
+    OutOfMoves,
+}
+
+/// Keeps track of the limits and the score of the game.
+pub struct Challenge {
+    /// The time allowed for each level
+    time_limit: Option<Duration>,
+    /// Whether the number of moves is limited by the par of each level
+    move_limit: bool,
+    /// Whether the levels are scored
+    marathon: bool,
+    /// The time at which the current level started
+    started: Instant,
+    /// The total score
+    score: u32,
+}
+
+impl Challenge {
+    /// The points earned for completing a level.
+    const LEVEL_POINTS: u32 = 1000;
+    /// The points lost for each move above the par of a level.
+    const MOVE_PENALTY: u32 = 10;
+    /// The points earned for each second left on the countdown.
+    const TIME_BONUS: u32 = 10;
+
+    /// Creates a new instance.
+    pub fn new(time_limit: Option<Duration>, move_limit: bool, marathon: bool) -> Challenge {
+        Challenge {
+            time_limit,
+            move_limit,
+            marathon,
+            started: Instant::now(),
+            score: 0,
+        }
+    }
+
+    /// Returns true if the countdown is enabled.
+    pub fn is_timed(&self) -> bool {
+        self.time_limit.is_some()
+    }
+
+    /// Returns true if the levels are scored.
+    pub fn is_marathon(&self) -> bool {
+        self.marathon
+    }
+
+    /// Returns the total score.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    /// Starts the countdown of a level, either new or restarted.
+    pub fn start(&mut self) {
+        self.started = Instant::now();
+    }
+
+    /// Returns the time left on the countdown.
+    pub fn remaining_time(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.started.elapsed()))
+    }
+
+    /// Returns the number of moves left before reaching the par of the level.
+    pub fn remaining_moves(&self, level: &Level) -> Option<i32> {
+        if self.move_limit {
+            level.par().map(|par| i32::max(0, par - level.get_steps()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the state of the given level.
+    pub fn status(&self, level: &Level) -> Status {
+        if level.is_completed() {
+            Status::Completed
+        } else if self.remaining_time() == Some(Duration::ZERO) {
+            Status::TimeUp
+        } else if self.remaining_moves(level) == Some(0) {
+            Status::OutOfMoves
+        } else {
+            Status::Playing
+        }
+    }
+
+    /// Adds the points earned for a completed level to the score.
+    ///
+    /// Each move above the par of the level costs points, and each second
+    /// left on the countdown is worth a bonus.
+    pub fn complete(&mut self, level: &Level) {
+        if !self.marathon {
+            return;
+        }
+        let penalty = level.par().map_or(0, |par| {
+            let extra = i32::max(0, level.get_steps() - par) as u32;
+            extra * Challenge::MOVE_PENALTY
+        });
+        let bonus = self
+            .remaining_time()
+            .map_or(0, |time| time.as_secs() as u32 * Challenge::TIME_BONUS);
+        let points = Challenge::LEVEL_POINTS.saturating_sub(penalty) + bonus;
+        self.score += points;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Direction;
+
+    fn level_with_par(par: i32) -> Level {
+        let mut level: Level = "#########\n#@   $ .#\n#########".parse().unwrap();
+        level.set_par(Some(par));
+        level
+    }
+
+    #[test]
+    fn time_up_when_countdown_reaches_zero() {
+        let level = level_with_par(10);
+        let mut challenge = Challenge::new(Some(Duration::from_secs(30)), false, false);
+        assert_eq!(challenge.status(&level), Status::Playing);
+
+        challenge.started -= Duration::from_secs(31);
+        assert_eq!(challenge.remaining_time(), Some(Duration::ZERO));
+        assert_eq!(challenge.status(&level), Status::TimeUp);
+    }
+
+    #[test]
+    fn out_of_moves_when_par_is_reached() {
+        let mut level = level_with_par(2);
+        let challenge = Challenge::new(None, true, false);
+
+        level.step(Direction::Right);
+        assert_eq!(challenge.remaining_moves(&level), Some(1));
+        assert_eq!(challenge.status(&level), Status::Playing);
+
+        level.step(Direction::Right);
+        assert_eq!(challenge.remaining_moves(&level), Some(0));
+        assert_eq!(challenge.status(&level), Status::OutOfMoves);
+    }
+
+    #[test]
+    fn completed_level_is_not_out_of_moves() {
+        let mut level = level_with_par(5);
+        let challenge = Challenge::new(None, true, false);
+        for _ in 0..5 {
+            level.step(Direction::Right);
+        }
+        assert_eq!(challenge.status(&level), Status::Completed);
+    }
+
+    #[test]
+    fn marathon_score_counts_moves_over_par_and_time_left() {
+        let mut level = level_with_par(2);
+        for _ in 0..5 {
+            level.step(Direction::Right);
+        }
+        assert!(level.is_completed());
+
+        let mut challenge = Challenge::new(Some(Duration::from_secs(60)), false, true);
+        challenge.started -= Duration::from_millis(20_500);
+        challenge.complete(&level);
+        assert_eq!(challenge.score(), 1000 - 3 * 10 + 39 * 10);
+
+        // Without a countdown, only the moves over par count
+        let mut challenge = Challenge::new(None, false, true);
+        challenge.complete(&level);
+        assert_eq!(challenge.score(), 1000 - 3 * 10);
+    }
+
+    #[test]
+    fn score_is_kept_only_in_marathon() {
+        let mut level = level_with_par(5);
+        for _ in 0..5 {
+            level.step(Direction::Right);
+        }
+        let mut challenge = Challenge::new(None, false, false);
+        challenge.complete(&level);
+        assert_eq!(challenge.score(), 0);
+    }
+}
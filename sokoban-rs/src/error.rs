@@ -24,6 +24,10 @@ use std::io;
 pub enum SokobanError {
     IoError(io::Error),
     ParseError(game::InvalidChar),
+    LevelNotFound(String),
+    InvalidSolution(String),
+    ImageTooLarge(u32, u32),
+    GifError(gif::EncodingError),
 }
 
 impl error::Error for SokobanError {
@@ -31,6 +35,10 @@ impl error::Error for SokobanError {
         match *self {
             SokobanError::IoError(..) => "I/O error",
             SokobanError::ParseError(..) => "Level parsing error",
+            SokobanError::LevelNotFound(..) => "Level not found",
+            SokobanError::InvalidSolution(..) => "Invalid solution",
+            SokobanError::ImageTooLarge(..) => "Image too large",
+            SokobanError::GifError(..) => "GIF encoding error",
         }
     }
 }
@@ -40,6 +48,10 @@ impl Display for SokobanError {
         match *self {
             SokobanError::IoError(ref err) => write!(f, "{}", *err),
             SokobanError::ParseError(ref err) => write!(f, "{}", *err),
+            SokobanError::LevelNotFound(ref level) => write!(f, "level `{}' not found", level),
+            SokobanError::InvalidSolution(ref msg) => write!(f, "invalid solution: {}", msg),
+            SokobanError::ImageTooLarge(w, h) => write!(f, "image too large ({}x{})", w, h),
+            SokobanError::GifError(ref err) => write!(f, "{}", *err),
         }
     }
 }
@@ -55,3 +67,9 @@ impl From<game::InvalidChar> for SokobanError {
         SokobanError::ParseError(err)
     }
 }
+
+impl From<gif::EncodingError> for SokobanError {
+    fn from(err: gif::EncodingError) -> Self {
+        SokobanError::GifError(err)
+    }
+}
@@ -0,0 +1,129 @@
+// This file is part of sokoban-rs
+// Copyright 2015 Sébastien Watteau
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export of solutions as animated GIF images.
+
+use gif::{Encoder, Frame, Repeat};
+use sdl2::{render::Canvas, video::Window};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::{
+    error::SokobanError,
+    game::{Direction, Level},
+    painter::Painter,
+    rules::Topology,
+};
+
+/// The minimum time in milliseconds during which the last frame is shown.
+const LAST_FRAME_DELAY: u16 = 1000;
+
+/// Parses a solution written for the given grid, ignoring whitespace.
+///
+/// See `Topology::parse_move` for the characters of each grid.
+pub fn parse_solution(solution: &str, topology: Topology) -> Result<Vec<Direction>, SokobanError> {
+    solution
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            topology.parse_move(c).ok_or_else(|| {
+                SokobanError::InvalidSolution(format!("unexpected character `{}'", c))
+            })
+        })
+        .collect()
+}
+
+/// Plays the solution on the given level and writes every step as a frame of
+/// an animated GIF image at the given path.
+///
+/// The delay between frames is given in milliseconds, rounded up to the
+/// hundredths of a second GIF images count in, and the tiles are scaled by
+/// the given factor. The solution does not need to complete the level, but
+/// every move must be possible.
+pub fn export_gif<P: AsRef<Path>>(
+    path: P,
+    painter: &mut Painter,
+    canvas: &mut Canvas<Window>,
+    level: &Level,
+    solution: &[Direction],
+    delay: u16,
+    scale: f64,
+) -> Result<(), SokobanError> {
+    // Check the solution before writing anything
+    let mut level = level.clone();
+    let mut levels = Vec::with_capacity(solution.len() + 1);
+    levels.push(level.clone());
+    for (i, &dir) in solution.iter().enumerate() {
+        let steps = level.get_steps();
+        level.step(dir);
+        if level.get_steps() == steps {
+            let msg = format!("move #{} is blocked", i + 1);
+            return Err(SokobanError::InvalidSolution(msg));
+        }
+        levels.push(level.clone());
+    }
+
+    let (width, height, _) = painter.render(canvas, &levels[0], scale);
+    let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(w), Ok(h)) => (w, h),
+        _ => return Err(SokobanError::ImageTooLarge(width, height)),
+    };
+    let writer = BufWriter::new(File::create(path.as_ref())?);
+    let mut encoder = Encoder::new(writer, width, height, &[])?;
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    for (i, level) in levels.iter().enumerate() {
+        // Leave the final position on screen for a while
+        let delay = if i + 1 == levels.len() {
+            u16::max(delay, LAST_FRAME_DELAY)
+        } else {
+            delay
+        };
+        let (_, _, mut pixels) = painter.render(canvas, level, scale);
+        let mut frame = Frame::from_rgba_speed(width, height, &mut pixels, 10);
+        frame.delay = delay.div_ceil(10).max(1);
+        encoder.write_frame(&frame)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hex_solution() {
+        let solution = parse_solution("lQ e\nzC r", Topology::Hexagonal).unwrap();
+        assert_eq!(
+            solution,
+            [
+                Direction::Left,
+                Direction::UpLeft,
+                Direction::UpRight,
+                Direction::DownLeft,
+                Direction::DownRight,
+                Direction::Right,
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_moves_of_other_grid() {
+        assert!(parse_solution("lurd", Topology::Hexagonal).is_err());
+        assert!(parse_solution("lqr", Topology::Square).is_err());
+    }
+}
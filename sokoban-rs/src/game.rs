@@ -13,12 +13,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use crate::rules::{Classic, Rules, Topology};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 
 /// Represents a direction.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
     /// Up
     Up,
@@ -28,6 +29,58 @@ pub enum Direction {
     Left,
     /// Right
     Right,
+    /// Up and left
+    UpLeft,
+    /// Up and right
+    UpRight,
+    /// Down and left
+    DownLeft,
+    /// Down and right
+    DownRight,
+}
+
+impl Direction {
+    /// Returns the direction matching a character of a solution in the LURD
+    /// format. Pushes are written in uppercase and other moves in lowercase.
+    pub fn from_lurd(c: char) -> Option<Direction> {
+        match c.to_ascii_lowercase() {
+            'l' => Some(Direction::Left),
+            'u' => Some(Direction::Up),
+            'r' => Some(Direction::Right),
+            'd' => Some(Direction::Down),
+            _ => None,
+        }
+    }
+
+    /// Returns the direction matching a character of a solution on a hexagonal
+    /// grid: `l` and `r` for left and right, `q` and `e` for up-left and
+    /// up-right, `z` and `c` for down-left and down-right, like the keys used
+    /// to play. Pushes are written in uppercase and other moves in lowercase.
+    pub fn from_hex(c: char) -> Option<Direction> {
+        match c.to_ascii_lowercase() {
+            'l' => Some(Direction::Left),
+            'r' => Some(Direction::Right),
+            'q' => Some(Direction::UpLeft),
+            'e' => Some(Direction::UpRight),
+            'z' => Some(Direction::DownLeft),
+            'c' => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+
+    /// Returns the opposite direction.
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
 }
 
 /// Represents a position in the world.
@@ -40,14 +93,10 @@ impl Position {
         Position(row, col)
     }
 
-    /// Returns the position that is next to this one in the given direction.
-    pub fn neighbor(&self, dir: Direction) -> Position {
-        match dir {
-            Direction::Up => Position(self.0 - 1, self.1),
-            Direction::Down => Position(self.0 + 1, self.1),
-            Direction::Left => Position(self.0, self.1 - 1),
-            Direction::Right => Position(self.0, self.1 + 1),
-        }
+    /// Returns the position the given number of rows and columns away from
+    /// this one.
+    pub fn offset(&self, rows: i32, cols: i32) -> Position {
+        Position(self.0 + rows, self.1 + cols)
     }
 
     /// Returns the row number.
@@ -66,6 +115,8 @@ impl Position {
 pub struct Level {
     /// The level's title
     title: String,
+    /// The number of moves of a good solution, if known
+    par: Option<i32>,
     /// The player's position
     player: Position,
     /// The current number of steps
@@ -76,25 +127,115 @@ pub struct Level {
     boxes: HashSet<Position>,
     /// The positions of the squares
     squares: HashSet<Position>,
+    /// The colors of the boxes, when not the default one
+    box_colors: HashMap<Position, u8>,
+    /// The colors of the squares, when not the default one
+    square_colors: HashMap<Position, u8>,
+    /// The rules of the variant being played
+    rules: &'static dyn Rules,
     /// The number of columns and rows in the level
     extents: (i32, i32),
+    /// Whether the player pulls boxes instead of pushing them
+    reverse: bool,
 }
 
 impl Level {
+    /// Returns the reverse version of this level, where the boxes start on the
+    /// squares and must be pulled back to their initial positions, or `None`
+    /// if there is no free cell left for the player.
+    pub fn reversed(&self) -> Option<Level> {
+        let mut level = self.clone();
+        level.boxes = self.squares.clone();
+        level.squares = self.boxes.clone();
+        level.box_colors = self.square_colors.clone();
+        level.square_colors = self.box_colors.clone();
+        level.steps = 0;
+        level.reverse = true;
+
+        // The player may be standing on a square that is now covered by a box
+        if level.boxes.contains(&level.player) {
+            level.player = level.nearest_free_cell()?;
+        }
+        Some(level)
+    }
+
+    /// Returns the free cell closest to the player, walking over the boxes but
+    /// not through the walls.
+    fn nearest_free_cell(&self) -> Option<Position> {
+        let (cols, rows) = self.extents;
+        let mut seen = HashSet::from([self.player]);
+        let mut queue = VecDeque::from([self.player]);
+        while let Some(pos) = queue.pop_front() {
+            if self.is_free(&pos) {
+                return Some(pos);
+            }
+            for &dir in self.directions() {
+                if let Some(next) = self.neighbor(&pos, dir)
+                    && (0..rows).contains(&next.row())
+                    && (0..cols).contains(&next.column())
+                    && !self.is_wall(&next)
+                    && seen.insert(next)
+                {
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns true if the player pulls boxes instead of pushing them.
+    pub fn is_reverse(&self) -> bool {
+        self.reverse
+    }
+
     /// Moves the player in the given direction if possible.
+    ///
+    /// In a reverse level, the box standing behind the player is pulled along.
     pub fn step(&mut self, dir: Direction) {
-        let next_to_player = self.player.neighbor(dir);
+        if self.reverse {
+            self.pull(dir);
+        } else {
+            self.push(dir);
+        }
+    }
+
+    /// Moves the player in the given direction, pushing the box in front of
+    /// them if possible.
+    fn push(&mut self, dir: Direction) {
+        let Some(next_to_player) = self.neighbor(&self.player, dir) else {
+            return;
+        };
         if self.is_free(&next_to_player) {
             self.move_player(next_to_player);
         } else if self.is_box(&next_to_player) {
-            let next_to_box = next_to_player.neighbor(dir);
-            if self.is_free(&next_to_box) {
+            let next_to_box = self.neighbor(&next_to_player, dir);
+            if let Some(next_to_box) = next_to_box
+                && self.is_free(&next_to_box)
+            {
                 self.move_box(&next_to_player, next_to_box);
                 self.move_player(next_to_player);
             }
         }
     }
 
+    /// Moves the player in the given direction, pulling the box behind them if
+    /// there is one.
+    fn pull(&mut self, dir: Direction) {
+        let Some(next_to_player) = self.neighbor(&self.player, dir) else {
+            return;
+        };
+        if self.is_free(&next_to_player) {
+            let behind_player = self.neighbor(&self.player, dir.opposite());
+            let player = self.player;
+            self.move_player(next_to_player);
+            if let Some(behind_player) = behind_player
+                && self.is_box(&behind_player)
+            {
+                self.move_box(&behind_player, player);
+            }
+        }
+    }
+
     /// Returns the current number of steps.
     pub fn get_steps(&self) -> i32 {
         self.steps
@@ -102,7 +243,33 @@ impl Level {
 
     /// Returns true if the level is completed.
     pub fn is_completed(&self) -> bool {
-        self.squares.difference(&self.boxes).count() == 0
+        self.rules.is_completed(self)
+    }
+
+    /// Returns the rules of the variant being played.
+    pub fn rules(&self) -> &'static dyn Rules {
+        self.rules
+    }
+
+    /// Returns the directions in which the player can move.
+    pub fn directions(&self) -> &'static [Direction] {
+        self.rules.topology().directions()
+    }
+
+    /// Returns the position next to the given one in the given direction, or
+    /// `None` if the grid has no neighbor in that direction.
+    pub fn neighbor(&self, pos: &Position, dir: Direction) -> Option<Position> {
+        self.rules.topology().neighbor(pos, dir)
+    }
+
+    /// Returns the color of the box at the given position, 0 being the default.
+    pub fn box_color(&self, pos: &Position) -> u8 {
+        self.box_colors.get(pos).cloned().unwrap_or(0)
+    }
+
+    /// Returns the color of the square at the given position, 0 being the default.
+    pub fn square_color(&self, pos: &Position) -> u8 {
+        self.square_colors.get(pos).cloned().unwrap_or(0)
     }
 
     /// Returns true if the given location is free.
@@ -130,6 +297,21 @@ impl Level {
         self.walls.contains(pos)
     }
 
+    /// Returns the player's position.
+    pub fn player(&self) -> Position {
+        self.player
+    }
+
+    /// Returns the positions of the boxes.
+    pub fn boxes(&self) -> impl Iterator<Item = &Position> {
+        self.boxes.iter()
+    }
+
+    /// Returns the positions of the squares.
+    pub fn squares(&self) -> impl Iterator<Item = &Position> {
+        self.squares.iter()
+    }
+
     /// Returns the number of columns and rows of this level.
     pub fn extents(&self) -> (i32, i32) {
         self.extents
@@ -145,6 +327,16 @@ impl Level {
         self.title = title.into();
     }
 
+    /// Returns the number of moves of a good solution, if known
+    pub fn par(&self) -> Option<i32> {
+        self.par
+    }
+
+    /// Changes the number of moves of a good solution
+    pub fn set_par(&mut self, par: Option<i32>) {
+        self.par = par;
+    }
+
     /// moves the player to the given position.
     fn move_player(&mut self, pos: Position) {
         if pos != self.player {
@@ -157,6 +349,9 @@ impl Level {
     fn move_box(&mut self, from: &Position, to: Position) {
         if self.boxes.remove(from) {
             self.boxes.insert(to);
+            if let Some(color) = self.box_colors.remove(from) {
+                self.box_colors.insert(to, color);
+            }
         }
     }
 }
@@ -182,50 +377,101 @@ impl FromStr for Level {
     type Err = InvalidChar;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Level::parse(s, &Classic)
+    }
+}
+
+impl Level {
+    /// Builds a level to be played with the given rules.
+    ///
+    /// When the rules use colors, the boxes and squares of the n-th color other
+    /// than the default one are written with the n-th uppercase and lowercase
+    /// letter respectively, and a box of the n-th color lying on a square of
+    /// the same color with the digit n. Letters and digits past the number of
+    /// colors of the rules are invalid.
+    ///
+    /// On a hexagonal grid, the cells of a row are written on every other
+    /// character, with spaces in between, and each row is shifted by one
+    /// character from its neighbors. A character other than a space lying
+    /// between two cells is invalid.
+    pub fn parse(s: &str, rules: &'static dyn Rules) -> Result<Level, InvalidChar> {
         let mut level = Level {
             title: String::new(),
+            par: None,
             player: Position(0, 0),
             steps: 0,
             walls: HashSet::new(),
             boxes: HashSet::new(),
             squares: HashSet::new(),
+            box_colors: HashMap::new(),
+            square_colors: HashMap::new(),
+            rules,
             extents: (0, 0),
+            reverse: false,
         };
 
-        let (mut row, mut col) = (0, 0);
-        for c in s.chars() {
-            let pos = Position(row, col);
-            match c {
-                '\n' => {
-                    row += 1;
-                    col = -1;
-                }
-                '#' => {
-                    level.walls.insert(pos);
-                }
-                '.' => {
-                    level.squares.insert(pos);
-                }
-                '$' => {
-                    level.boxes.insert(pos);
-                }
-                '@' => {
-                    level.player = pos;
-                }
-                '+' => {
-                    level.player = pos;
-                    level.squares.insert(pos);
-                }
-                '*' => {
-                    level.boxes.insert(pos);
-                    level.squares.insert(pos);
+        // The parity of the characters holding cells on a hexagonal grid is
+        // given by the first cell of the level
+        let parity = s
+            .lines()
+            .enumerate()
+            .find_map(|(row, line)| line.chars().position(|c| c != ' ').map(|x| row + x))
+            .map_or(0, |first| (first % 2) as i32);
+
+        for (row, line) in s.lines().enumerate() {
+            let row = row as i32;
+            for (x, c) in line.chars().enumerate() {
+                let x = x as i32;
+                if c == ' ' {
+                    continue;
                 }
-                ' ' => {}
-                _ => {
-                    return Err(InvalidChar(c, pos));
+                let pos = match rules.topology() {
+                    Topology::Square => Position(row, x),
+                    Topology::Hexagonal if (row + x + parity) % 2 != 0 => {
+                        return Err(InvalidChar(c, Position(row, x)));
+                    }
+                    Topology::Hexagonal => Position(row, (x + parity - row % 2) / 2),
+                };
+                match c {
+                    '#' => {
+                        level.walls.insert(pos);
+                    }
+                    '.' => {
+                        level.squares.insert(pos);
+                    }
+                    '$' => {
+                        level.boxes.insert(pos);
+                    }
+                    '@' => {
+                        level.player = pos;
+                    }
+                    '+' => {
+                        level.player = pos;
+                        level.squares.insert(pos);
+                    }
+                    '*' => {
+                        level.boxes.insert(pos);
+                        level.squares.insert(pos);
+                    }
+                    'A'..='Z' if c as u8 - b'A' < rules.colors() => {
+                        level.boxes.insert(pos);
+                        level.box_colors.insert(pos, c as u8 - b'A' + 1);
+                    }
+                    'a'..='z' if c as u8 - b'a' < rules.colors() => {
+                        level.squares.insert(pos);
+                        level.square_colors.insert(pos, c as u8 - b'a' + 1);
+                    }
+                    '1'..='9' if c as u8 - b'1' < rules.colors() => {
+                        level.boxes.insert(pos);
+                        level.squares.insert(pos);
+                        level.box_colors.insert(pos, c as u8 - b'0');
+                        level.square_colors.insert(pos, c as u8 - b'0');
+                    }
+                    _ => {
+                        return Err(InvalidChar(c, Position(row, x)));
+                    }
                 }
             }
-            col += 1;
         }
 
         // Calculate the extents of the level
@@ -248,3 +494,126 @@ impl FromStr for Level {
         Ok(level)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::{Colored, Hexoban};
+
+    #[test]
+    fn pull_drags_box_behind_player() {
+        let level: Level = "######\n#*@  #\n######".parse().unwrap();
+        let mut level = level.reversed().unwrap();
+
+        level.step(Direction::Right);
+        assert_eq!(level.player(), Position::new(1, 3));
+        assert!(level.is_box(&Position::new(1, 2)));
+        assert!(!level.is_box(&Position::new(1, 1)));
+        assert_eq!(level.get_steps(), 1);
+    }
+
+    #[test]
+    fn pull_cannot_push() {
+        let level: Level = "######\n#*@  #\n######".parse().unwrap();
+        let mut level = level.reversed().unwrap();
+
+        level.step(Direction::Left);
+        assert_eq!(level.player(), Position::new(1, 2));
+        assert!(level.is_box(&Position::new(1, 1)));
+        assert_eq!(level.get_steps(), 0);
+    }
+
+    #[test]
+    fn pull_without_box_only_moves_player() {
+        let level: Level = "######\n#* @ #\n######".parse().unwrap();
+        let mut level = level.reversed().unwrap();
+
+        level.step(Direction::Right);
+        assert_eq!(level.player(), Position::new(1, 4));
+        assert!(level.is_box(&Position::new(1, 1)));
+    }
+
+    #[test]
+    fn reversed_moves_player_off_covered_square() {
+        let level: Level = "#######\n#*+*$ #\n#######".parse().unwrap();
+        let level = level.reversed().unwrap();
+        assert_eq!(level.player(), Position::new(1, 4));
+    }
+
+    #[test]
+    fn reversed_rejects_level_without_free_cell() {
+        let level: Level = "#####\n#*+*#\n#####".parse().unwrap();
+        assert!(level.reversed().is_none());
+    }
+
+    #[test]
+    fn hexagonal_neighbors_of_even_row() {
+        let pos = Position::new(2, 3);
+        let neighbor = |dir| Topology::Hexagonal.neighbor(&pos, dir);
+        assert_eq!(neighbor(Direction::Left), Some(Position::new(2, 2)));
+        assert_eq!(neighbor(Direction::Right), Some(Position::new(2, 4)));
+        assert_eq!(neighbor(Direction::UpLeft), Some(Position::new(1, 2)));
+        assert_eq!(neighbor(Direction::UpRight), Some(Position::new(1, 3)));
+        assert_eq!(neighbor(Direction::DownLeft), Some(Position::new(3, 2)));
+        assert_eq!(neighbor(Direction::DownRight), Some(Position::new(3, 3)));
+        assert_eq!(neighbor(Direction::Up), None);
+    }
+
+    #[test]
+    fn hexagonal_neighbors_of_odd_row() {
+        let pos = Position::new(3, 3);
+        let neighbor = |dir| Topology::Hexagonal.neighbor(&pos, dir);
+        assert_eq!(neighbor(Direction::Left), Some(Position::new(3, 2)));
+        assert_eq!(neighbor(Direction::Right), Some(Position::new(3, 4)));
+        assert_eq!(neighbor(Direction::UpLeft), Some(Position::new(2, 3)));
+        assert_eq!(neighbor(Direction::UpRight), Some(Position::new(2, 4)));
+        assert_eq!(neighbor(Direction::DownLeft), Some(Position::new(4, 3)));
+        assert_eq!(neighbor(Direction::DownRight), Some(Position::new(4, 4)));
+        assert_eq!(neighbor(Direction::Down), None);
+    }
+
+    #[test]
+    fn parse_hexagonal_level_with_spacing() {
+        let level = Level::parse(" # # # #\n# . $ @ #\n # # # #", &Hexoban).unwrap();
+        assert_eq!(level.player(), Position::new(1, 3));
+        assert!(level.is_box(&Position::new(1, 2)));
+        assert!(level.is_square(&Position::new(1, 1)));
+        assert!(level.is_wall(&Position::new(0, 1)));
+        assert!(level.is_wall(&Position::new(1, 0)));
+        assert_eq!(level.extents(), (5, 3));
+
+        let mut level = level;
+        level.step(Direction::Left);
+        assert!(level.is_completed());
+    }
+
+    #[test]
+    fn parse_rejects_cell_between_hexagonal_cells() {
+        assert!(Level::parse("# # #\n #@ #\n# # #", &Hexoban).is_err());
+    }
+
+    #[test]
+    fn colored_box_must_fit_its_square() {
+        let mut level = Level::parse("######\n#@Ab #\n######", &Colored).unwrap();
+        level.step(Direction::Right);
+        assert!(level.is_box(&Position::new(1, 3)));
+        assert!(!level.is_completed());
+
+        let mut level = Level::parse("######\n#@Aa #\n######", &Colored).unwrap();
+        level.step(Direction::Right);
+        assert!(level.is_completed());
+    }
+
+    #[test]
+    fn parse_colored_box_on_its_square() {
+        let level = Level::parse("#####\n#@2 #\n#####", &Colored).unwrap();
+        let pos = Position::new(1, 2);
+        assert!(level.is_box(&pos) && level.is_square(&pos));
+        assert_eq!(level.box_color(&pos), 2);
+        assert_eq!(level.square_color(&pos), 2);
+        assert!(level.is_completed());
+
+        assert!(Level::parse("#@7#", &Colored).is_err());
+        assert!("#@1#".parse::<Level>().is_err());
+    }
+}
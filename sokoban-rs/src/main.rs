@@ -18,29 +18,39 @@
 #[macro_use]
 extern crate bitflags;
 
-use clap::{CommandFactory, Parser, error::ErrorKind};
+use clap::{Args, CommandFactory, Parser, Subcommand, error::ErrorKind};
 use sdl2::Sdl;
 use sdl2::event::Event;
 use sdl2::image::InitFlag;
 use sdl2::image::LoadTexture;
 use sdl2::keyboard::Keycode;
 use sdl2::render::{Canvas, TextureCreator};
+use sdl2::ttf::Sdl2TtfContext;
 use sdl2::video::{Window, WindowContext};
 use std::error::Error;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
+use std::time::Duration;
 use xml::reader::{EventReader, XmlEvent};
 
+pub mod camera;
+pub mod challenge;
 pub mod error;
+pub mod export;
 pub mod game;
 pub mod painter;
+pub mod rules;
 pub mod shadow;
+pub mod solver;
 pub mod tileset;
 
+use camera::Camera;
+use challenge::{Challenge, Status};
+use error::SokobanError;
 use game::{Direction, Level};
 use painter::Painter;
+use rules::{Rules, Topology, Variant};
 use tileset::Tileset;
 
 #[derive(Parser, Debug)]
@@ -50,16 +60,39 @@ use tileset::Tileset;
     about = "An implementation of Sokoban in the Rust programming language.",
     author = "Sébastien Watteau",
     arg_required_else_help = true,
-    disable_help_flag = true
+    disable_help_flag = true,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
 struct Cli {
     /// a Sokoban level collection (SLC) file
-    slc_file: PathBuf,
+    #[arg(required = true)]
+    slc_file: Option<PathBuf>,
 
     /// Launches the game in fullscreen mode
     #[arg(short = 'f', long = "fullscreen")]
     fullscreen: bool,
 
+    /// Starts each level with the boxes on the squares, to be pulled back
+    #[arg(short = 'r', long = "reverse")]
+    reverse: bool,
+
+    /// The rules of the levels in the collection
+    #[arg(long = "variant", value_enum, default_value_t = Variant::Classic)]
+    variant: Variant,
+
+    /// Gives the given number of seconds to complete each level
+    #[arg(short = 't', long = "time-limit", value_parser = clap::value_parser!(u64))]
+    time_limit: Option<u64>,
+
+    /// Limits the number of moves to the par of each level, when known
+    #[arg(short = 'm', long = "move-limit")]
+    move_limit: bool,
+
+    /// Scores the whole collection, without skipping levels
+    #[arg(long = "marathon")]
+    marathon: bool,
+
     /// The width of the window in pixels
     #[arg(short = 'w', long = "width", value_parser = clap::value_parser!(u32))]
     width: Option<u32>,
@@ -71,11 +104,61 @@ struct Cli {
     /// Print help information
     #[arg(long = "help", action = clap::ArgAction::Help)]
     help: Option<bool>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Exports the solution of a level as an animated GIF image
+    ExportGif(ExportGifArgs),
+}
+
+#[derive(Args, Debug)]
+struct ExportGifArgs {
+    /// a Sokoban level collection (SLC) file
+    slc_file: PathBuf,
+
+    /// The title of the level, or its number in the collection starting at 1
+    level: String,
+
+    /// The solution in the LURD format, or with the LRQEZC moves on a
+    /// hexagonal grid
+    solution: String,
+
+    /// The GIF file to write
+    #[arg(short = 'o', long = "output", default_value = "solution.gif")]
+    output: PathBuf,
+
+    /// The delay between two frames in milliseconds
+    #[arg(short = 'd', long = "delay", default_value_t = 100)]
+    delay: u16,
+
+    /// The scale factor applied to the tiles
+    #[arg(short = 's', long = "scale", default_value_t = 0.5, value_parser = parse_scale)]
+    scale: f64,
+
+    /// The rules of the levels in the collection
+    #[arg(long = "variant", value_enum, default_value_t = Variant::Classic)]
+    variant: Variant,
+}
+
+/// Parses a scale factor, which must be a positive number.
+fn parse_scale(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(scale) if scale > 0.0 && scale.is_finite() => Ok(scale),
+        Ok(_) => Err(String::from("the scale factor must be a positive number")),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 pub fn main() -> Result<(), Box<dyn Error>> {
     // Read command line arguments
     let cli = Cli::parse();
+    if let Some(Command::ExportGif(args)) = cli.command {
+        return export(args);
+    }
     let (width, height) = match (cli.width, cli.height) {
         (Some(w), Some(h)) => (w, h),
         (None, None) => (1024, 768),
@@ -89,10 +172,16 @@ pub fn main() -> Result<(), Box<dyn Error>> {
         }
     };
     let fullscreen = cli.fullscreen;
-    let slc_file = cli.slc_file;
+    let reverse = cli.reverse;
+    let mut challenge = Challenge::new(
+        cli.time_limit.map(Duration::from_secs),
+        cli.move_limit,
+        cli.marathon,
+    );
+    let slc_file = cli.slc_file.expect("the SLC file is required");
 
     // Load the level collection file
-    let levels = load_slc_file(slc_file)?;
+    let levels = load_slc_file(slc_file, cli.variant.rules())?;
 
     // Initialize SDL components
     let sdl = sdl2::init()?;
@@ -103,34 +192,73 @@ pub fn main() -> Result<(), Box<dyn Error>> {
     let mut canvas = window.into_canvas().build()?;
     let texture_creator = canvas.texture_creator();
 
-    let mut painter = {
-        let big_set = load_tileset(
-            &texture_creator,
-            "assets/image/tileset.png",
-            101,
-            171,
-            83,
-            40,
-        )?;
-        let small_set = load_tileset(
-            &texture_creator,
-            "assets/image/tileset-small.png",
-            50,
-            85,
-            41,
-            20,
-        )?;
-        let font = ttf_context.load_font("assets/font/RujisHandwritingFontv.2.0.ttf", 20)?;
-        Painter::new(&mut canvas, big_set, small_set, font)
-    };
+    let mut painter = create_painter(&mut canvas, &texture_creator, &ttf_context)?;
 
-    mainloop(&sdl, levels.iter(), &mut painter, &mut canvas);
+    mainloop(
+        &sdl,
+        levels.iter(),
+        reverse,
+        &mut challenge,
+        &mut painter,
+        &mut canvas,
+    );
+
+    if challenge.is_marathon() {
+        println!("Final score: {}", challenge.score());
+    }
+
+    Ok(())
+}
+
+/// Exports the solution of a level as an animated GIF image.
+fn export(args: ExportGifArgs) -> Result<(), Box<dyn Error>> {
+    let levels = load_slc_file(&args.slc_file, args.variant.rules())?;
+    let level = find_level(&levels, &args.level)
+        .ok_or_else(|| SokobanError::LevelNotFound(args.level.clone()))?;
+    let topology = args.variant.rules().topology();
+    let solution = export::parse_solution(&args.solution, topology)?;
+
+    // Render off-screen through a hidden window
+    let sdl = sdl2::init()?;
+    let _ = sdl2::image::init(InitFlag::PNG)?;
+    let ttf_context = sdl2::ttf::init()?;
+    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "linear");
+
+    let window = sdl.video()?.window("sokoban-rs", 1, 1).hidden().build()?;
+    let mut canvas = window.into_canvas().build()?;
+    let texture_creator = canvas.texture_creator();
+    let mut painter = create_painter(&mut canvas, &texture_creator, &ttf_context)?;
+
+    export::export_gif(
+        &args.output,
+        &mut painter,
+        &mut canvas,
+        level,
+        &solution,
+        args.delay,
+        args.scale,
+    )?;
 
     Ok(())
 }
 
-/// Builds levels from a level collection file in the SLC format.
-fn load_slc_file<P: AsRef<Path>>(path: P) -> Result<Vec<Level>, error::SokobanError> {
+/// Returns the level with the given title, or with the given number starting at 1.
+fn find_level<'a>(levels: &'a [Level], name: &str) -> Option<&'a Level> {
+    levels
+        .iter()
+        .find(|level| level.title() == name)
+        .or_else(|| {
+            let n = name.parse::<usize>().ok()?;
+            levels.get(n.checked_sub(1)?)
+        })
+}
+
+/// Builds levels from a level collection file in the SLC format, to be played
+/// with the given rules.
+fn load_slc_file<P: AsRef<Path>>(
+    path: P,
+    rules: &'static dyn Rules,
+) -> Result<Vec<Level>, error::SokobanError> {
     let mut levels = Vec::new();
 
     let parser = {
@@ -139,36 +267,37 @@ fn load_slc_file<P: AsRef<Path>>(path: P) -> Result<Vec<Level>, error::SokobanEr
     };
 
     let mut level_title = String::new();
+    let mut level_par = None;
     let mut level_data = String::new();
     let mut reading_level = false;
     for event in parser {
         match event {
+            Ok(XmlEvent::StartElement { ref name, .. }) if name.local_name == "L" => {
+                reading_level = true;
+            }
             Ok(XmlEvent::StartElement {
                 ref name,
                 ref attributes,
                 ..
-            }) => {
-                if name.local_name == "L" {
-                    reading_level = true;
-                } else if name.local_name == "Level" {
-                    if let Some(id) = attributes.iter().find(|&attr| attr.name.local_name == "Id") {
-                        level_title = id.value.clone();
-                    }
+            }) if name.local_name == "Level" => {
+                if let Some(id) = attributes.iter().find(|&attr| attr.name.local_name == "Id") {
+                    level_title = id.value.clone();
                 }
+                level_par = attributes
+                    .iter()
+                    .find(|&attr| attr.name.local_name == "Par")
+                    .and_then(|attr| attr.value.parse().ok());
             }
-            Ok(XmlEvent::EndElement { name }) => {
-                if name.local_name == "Level" {
-                    let mut level = Level::from_str(&level_data)?;
-                    level.set_title(level_title.clone());
-                    levels.push(level);
-                    level_data.clear();
-                }
+            Ok(XmlEvent::EndElement { ref name }) if name.local_name == "Level" => {
+                let mut level = Level::parse(&level_data, rules)?;
+                level.set_title(level_title.clone());
+                level.set_par(level_par);
+                levels.push(level);
+                level_data.clear();
             }
-            Ok(XmlEvent::Characters(ref data)) => {
-                if reading_level {
-                    level_data.push_str(data);
-                    level_data.push('\n');
-                }
+            Ok(XmlEvent::Characters(ref data)) if reading_level => {
+                level_data.push_str(data);
+                level_data.push('\n');
             }
             _ => {}
         }
@@ -194,6 +323,32 @@ fn create_window(
     Ok(window)
 }
 
+/// Creates the painter along with its tilesets and font.
+fn create_painter<'a>(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &'a TextureCreator<WindowContext>,
+    ttf_context: &'a Sdl2TtfContext,
+) -> Result<Painter<'a>, Box<dyn Error>> {
+    let big_set = load_tileset(
+        texture_creator,
+        "assets/image/tileset.png",
+        101,
+        171,
+        83,
+        40,
+    )?;
+    let small_set = load_tileset(
+        texture_creator,
+        "assets/image/tileset-small.png",
+        50,
+        85,
+        41,
+        20,
+    )?;
+    let font = ttf_context.load_font("assets/font/RujisHandwritingFontv.2.0.ttf", 20)?;
+    Ok(Painter::new(canvas, big_set, small_set, font))
+}
+
 /// Loads a tileset
 fn load_tileset<P: AsRef<Path>>(
     texture_creator: &TextureCreator<WindowContext>,
@@ -212,26 +367,51 @@ fn load_tileset<P: AsRef<Path>>(
 fn mainloop<'a, I: Iterator<Item = &'a Level>>(
     sdl: &Sdl,
     mut levels: I,
+    reverse: bool,
+    challenge: &mut Challenge,
     painter: &mut Painter,
     canvas: &mut Canvas<Window>,
 ) {
-    let (mut reference_level, mut level) = match levels.next() {
-        Some(l) => (l, l.clone()),
+    // Levels that cannot be reversed are skipped
+    let prepare = |l: &Level| {
+        if !reverse {
+            return Some(l.clone());
+        }
+        let reversed = l.reversed();
+        if reversed.is_none() {
+            eprintln!("level `{}' cannot be played in reverse", l.title());
+        }
+        reversed
+    };
+    let mut reference_level = match levels.find_map(prepare) {
+        Some(l) => l,
         None => {
             return;
         }
     };
+    let mut level = reference_level.clone();
+    let mut camera = Camera::new();
+    camera.reset(&level);
+    challenge.start();
 
     let mut running = true;
     let mut events = sdl.event_pump().unwrap();
     let mut skip = false;
+    let mut hint = None;
     while running {
-        if level.is_completed() || skip {
-            match levels.next() {
+        let status = challenge.status(&level);
+        if status == Status::Completed {
+            challenge.complete(&level);
+        }
+        if status == Status::Completed || skip {
+            match levels.find_map(prepare) {
                 Some(l) => {
                     reference_level = l;
-                    level = l.clone();
+                    level = reference_level.clone();
+                    camera.reset(&level);
+                    challenge.start();
                     skip = false;
+                    continue;
                 }
                 None => {
                     break;
@@ -239,9 +419,23 @@ fn mainloop<'a, I: Iterator<Item = &'a Level>>(
             }
         }
 
-        painter.paint(canvas, &level);
+        camera.update(&level);
+        painter.paint(canvas, &level, &camera, challenge, hint.as_ref());
 
-        match events.wait_event() {
+        // Wake up regularly to refresh the countdown
+        let event = if challenge.is_timed() && status == Status::Playing {
+            match events.wait_event_timeout(100) {
+                Some(event) => event,
+                None => continue,
+            }
+        } else {
+            events.wait_event()
+        };
+        let playing = status == Status::Playing;
+        if let Event::KeyDown { .. } = event {
+            hint = None;
+        }
+        match event {
             Event::Quit { .. }
             | Event::KeyDown {
                 keycode: Some(Keycode::Escape),
@@ -250,39 +444,112 @@ fn mainloop<'a, I: Iterator<Item = &'a Level>>(
             Event::KeyDown {
                 keycode: Some(Keycode::Left),
                 ..
-            } => {
+            } if playing => {
                 level.step(Direction::Left);
             }
             Event::KeyDown {
                 keycode: Some(Keycode::Right),
                 ..
-            } => {
+            } if playing => {
                 level.step(Direction::Right);
             }
             Event::KeyDown {
                 keycode: Some(Keycode::Up),
                 ..
-            } => {
+            } if playing => {
                 level.step(Direction::Up);
             }
             Event::KeyDown {
                 keycode: Some(Keycode::Down),
                 ..
-            } => {
+            } if playing => {
                 level.step(Direction::Down);
             }
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Q | Keycode::E | Keycode::Z | Keycode::C)),
+                ..
+            } if playing && level.rules().topology() == Topology::Hexagonal => {
+                let dir = match keycode {
+                    Keycode::Q => Direction::UpLeft,
+                    Keycode::E => Direction::UpRight,
+                    Keycode::Z => Direction::DownLeft,
+                    _ => Direction::DownRight,
+                };
+                level.step(dir);
+            }
             Event::KeyDown {
                 keycode: Some(Keycode::R),
                 ..
             } => {
                 level = reference_level.clone();
+                challenge.start();
             }
             Event::KeyDown {
                 keycode: Some(Keycode::N),
                 ..
-            } => {
+            } if !challenge.is_marathon() || !playing => {
                 skip = true;
             }
+            Event::KeyDown {
+                keycode: Some(Keycode::H),
+                ..
+            } if playing => {
+                hint = solver::hint(&level);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Equals | Keycode::Plus | Keycode::KpPlus),
+                ..
+            } => {
+                camera.zoom_by(1);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::Minus | Keycode::KpMinus),
+                ..
+            } => {
+                camera.zoom_by(-1);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::W),
+                ..
+            } => {
+                camera.pan(&level, 0.0, -1.0);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::S),
+                ..
+            } => {
+                camera.pan(&level, 0.0, 1.0);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::A),
+                ..
+            } => {
+                camera.pan(&level, -1.0, 0.0);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::D),
+                ..
+            } => {
+                camera.pan(&level, 1.0, 0.0);
+            }
+            Event::KeyDown {
+                keycode: Some(Keycode::F),
+                ..
+            } => {
+                camera.follow(&level);
+            }
+            Event::MouseWheel { y, .. } => {
+                camera.zoom_by(y);
+            }
+            Event::MouseMotion {
+                mousestate,
+                xrel,
+                yrel,
+                ..
+            } if mousestate.left() => {
+                let (w, h) = painter.cell_size();
+                camera.pan(&level, -f64::from(xrel) / w, -f64::from(yrel) / h);
+            }
             _ => {}
         }
     }
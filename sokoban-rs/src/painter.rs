@@ -16,14 +16,20 @@
 use sdl2::{
     pixels::{Color, PixelFormatEnum},
     rect::Rect,
-    render::Canvas,
+    render::{BlendMode, Canvas},
     ttf::Font,
     video::Window,
 };
 
+use std::cmp;
+
 use crate::{
-    game::{Direction, Level, Position},
+    camera::Camera,
+    challenge::{Challenge, Status},
+    game::{Level, Position},
+    rules::Topology,
     shadow::ShadowFlags,
+    solver::Hint,
     tileset::{Tile, Tileset, TilesetSelector},
 };
 
@@ -41,12 +47,19 @@ pub struct Painter<'a> {
     bar_color: Color,
     /// The color of the text in the status bar
     bar_text_color: Color,
+    /// The color used to highlight a hint
+    hint_color: Color,
+    /// The maximum width and height of the minimap
+    minimap_size: u32,
+    /// The size of a cell on the screen when last painted
+    cell_size: (f64, f64),
 }
 
 /// Represents a location for text in the status bar
 #[derive(Clone, Copy)]
 enum StatusBarLocation {
     FlushLeft,
+    Centered,
     FlushRight,
 }
 
@@ -67,58 +80,148 @@ impl<'a> Painter<'a> {
             bar_height: 32,
             bar_color: Color::RGBA(20, 20, 20, 255),
             bar_text_color: Color::RGBA(255, 192, 0, 255),
+            hint_color: Color::RGBA(255, 64, 64, 255),
+            minimap_size: 160,
+            cell_size: (1.0, 1.0),
         }
     }
 
-    /// Paints a level onto the screen.
-    pub fn paint(&mut self, canvas: &mut Canvas<Window>, level: &Level) {
-        self.selector.reset(level.extents());
+    /// Paints a level onto the screen as seen by the camera, along with the
+    /// state of the challenge, highlighting the given hint if any.
+    pub fn paint(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        level: &Level,
+        camera: &Camera,
+        challenge: &Challenge,
+        hint: Option<&Hint>,
+    ) {
+        self.selector.reset(level.extents(), camera.zoom());
 
-        // Draw a full-size image onto an off-screen buffer
-        let fullsize = self.tileset().get_rendering_size(level.extents());
+        // Draw the visible part of the full-size image onto an off-screen buffer
+        let topology = level.rules().topology();
+        let fullsize = self.tileset().get_rendering_size(level.extents(), topology);
+        let scale = self.get_fit_ratio(level) * camera.zoom();
+        let region = self.get_visible_region(fullsize, scale, camera.center());
         let creator = canvas.texture_creator();
         let mut texture = creator
-            .create_texture_target(PixelFormatEnum::RGBA8888, fullsize.0, fullsize.1)
+            .create_texture_target(PixelFormatEnum::RGBA8888, region.width(), region.height())
             .expect("Could not get texture target for off-screen rendering");
 
         canvas
             .with_texture_canvas(&mut texture, |cv| {
-                self.paint_fullsize(cv, level);
+                self.paint_region(cv, level, &region);
+                if let Some(hint) = hint {
+                    self.paint_hint(cv, level, hint, &region);
+                }
             })
             .unwrap();
 
         // Copy onto the screen with appropriate scaling
-        let final_rect = self.get_centered_image_rect(self.get_scaled_rendering_size(&level));
+        let scaled = |sz: u32| (scale * f64::from(sz)).floor() as u32;
+        let final_rect =
+            self.get_centered_image_rect((scaled(region.width()), scaled(region.height())));
+        self.cell_size = (
+            scale * f64::from(self.tileset().width()),
+            scale * f64::from(self.tileset().effective_height()),
+        );
 
         canvas.clear();
-        let original_rect = Some(Rect::new(0, 0, fullsize.0, fullsize.1));
-        canvas.copy(&texture, original_rect, final_rect).unwrap();
+        canvas.copy(&texture, None, final_rect).unwrap();
+
+        // Show the whole level in a corner when only a part of it is visible
+        if region.width() < fullsize.0 || region.height() < fullsize.1 {
+            self.paint_minimap(canvas, level, &region);
+        }
 
-        self.paint_status_bar(canvas, &level);
+        self.paint_status_bar(canvas, level, challenge);
 
         canvas.present();
     }
 
-    /// Paints a full-size image of the given level onto the current render target.
-    fn paint_fullsize(&mut self, canvas: &mut Canvas<Window>, level: &Level) {
+    /// Returns the size in pixels of a cell of the level, as last painted on the screen.
+    pub fn cell_size(&self) -> (f64, f64) {
+        self.cell_size
+    }
+
+    /// Renders the full-size image of a level off-screen, scaled by the given
+    /// factor, and returns its width, its height and its pixels in RGBA order.
+    pub fn render(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        level: &Level,
+        scale: f64,
+    ) -> (u32, u32, Vec<u8>) {
+        self.selector.reset(level.extents(), 1.0);
+
+        let topology = level.rules().topology();
+        let fullsize = self.tileset().get_rendering_size(level.extents(), topology);
+        let region = Rect::new(0, 0, fullsize.0, fullsize.1);
+        let creator = canvas.texture_creator();
+        let mut texture = creator
+            .create_texture_target(PixelFormatEnum::RGBA8888, fullsize.0, fullsize.1)
+            .expect("Could not get texture target for off-screen rendering");
+        canvas
+            .with_texture_canvas(&mut texture, |cv| {
+                self.paint_region(cv, level, &region);
+            })
+            .unwrap();
+
+        // Scale the image through a second off-screen buffer
+        let scaled = |sz: u32| cmp::max(1, (scale * f64::from(sz)).floor() as u32);
+        let (w, h) = (scaled(fullsize.0), scaled(fullsize.1));
+        let mut target = creator
+            .create_texture_target(PixelFormatEnum::RGBA8888, w, h)
+            .expect("Could not get texture target for off-screen rendering");
+        let mut pixels = Vec::new();
+        canvas
+            .with_texture_canvas(&mut target, |cv| {
+                cv.copy(&texture, None, None).unwrap();
+                pixels = cv.read_pixels(None, PixelFormatEnum::RGBA32).unwrap();
+            })
+            .unwrap();
+
+        (w, h, pixels)
+    }
+
+    /// Paints the given region of the full-size image of a level onto the
+    /// current render target.
+    fn paint_region(&mut self, canvas: &mut Canvas<Window>, level: &Level, region: &Rect) {
         let (cols, rows) = level.extents();
+        let topology = level.rules().topology();
         canvas.set_draw_color(Color::RGB(0, 0, 0));
         canvas.clear();
 
-        for r in 0..rows {
-            for c in 0..cols {
+        // Items are drawn above their floor tile, so extra rows may be visible
+        let (w, h) = (
+            self.tileset().width() as i32,
+            self.tileset().height() as i32,
+        );
+        let eh = self.tileset().effective_height() as i32;
+        let first_col = cmp::max(0, region.x() / w);
+        let last_col = cmp::min(cols, region.right() / w + 1);
+        let first_row = cmp::max(0, (region.y() - h) / eh);
+        let last_row = cmp::min(rows, (region.bottom() + self.tileset().offset()) / eh + 1);
+
+        for r in first_row..last_row {
+            for c in first_col..last_col {
                 let pos = Position::new(r, c);
-                let (x, y) = self.tileset().get_coordinates(&pos);
+                let (x, y) = self.tileset().get_coordinates(&pos, topology);
+                let (x, y) = (x - region.x(), y - region.y());
 
                 // First paint the floor tiles
                 if level.is_square(&pos) {
-                    self.paint_tile(canvas, Tile::Square, x, y);
+                    let color = level.square_color(&pos);
+                    self.paint_tile(canvas, Tile::ColoredSquare(color), x, y);
                 } else {
                     self.paint_tile(canvas, Tile::Floor, x, y);
                 }
 
-                // Add the shadows
-                let flags = get_shadow_flags(&level, &pos);
+                // Add the shadows, which only fit square cells
+                let flags = match topology {
+                    Topology::Square => get_shadow_flags(level, &pos),
+                    Topology::Hexagonal => ShadowFlags::empty(),
+                };
                 for f in &[
                     ShadowFlags::N_EDGE,
                     ShadowFlags::S_EDGE,
@@ -140,7 +243,8 @@ impl<'a> Painter<'a> {
                     self.paint_tile(canvas, Tile::Wall, x, z);
                 }
                 if level.is_box(&pos) {
-                    self.paint_tile(canvas, Tile::Rock, x, z);
+                    let color = level.box_color(&pos);
+                    self.paint_tile(canvas, Tile::ColoredRock(color), x, z);
                 }
                 if level.is_player(&pos) {
                     self.paint_tile(canvas, Tile::Player, x, z);
@@ -149,8 +253,137 @@ impl<'a> Painter<'a> {
         }
     }
 
+    /// Paints a frame around the box of a hint and an arrow showing its direction.
+    fn paint_hint(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        level: &Level,
+        hint: &Hint,
+        region: &Rect,
+    ) {
+        let topology = level.rules().topology();
+        let Some(target) = level.neighbor(&hint.position, hint.direction) else {
+            return;
+        };
+        let (x, y) = self.tileset().get_coordinates(&hint.position, topology);
+        let (tx, ty) = self.tileset().get_coordinates(&target, topology);
+        let (x, y) = (x - region.x(), y - region.y());
+        let (w, h) = (self.tileset().width(), self.tileset().effective_height());
+        let z = y + self.tileset().offset();
+        let prev_color = canvas.draw_color();
+        canvas.set_draw_color(self.hint_color);
+
+        // Frame the box with a thick border
+        let thickness = 3;
+        for i in 0..thickness {
+            let rect = Rect::new(x + i, z + i, w - 2 * i as u32, h - 2 * i as u32);
+            canvas.draw_rect(rect).unwrap();
+        }
+
+        // Draw an arrow from the center of the box towards the next cell
+        let (cx, cy) = (x + w as i32 / 2, z + h as i32 / 2);
+        let (dx, dy) = (
+            f64::from(tx - region.x() - x),
+            f64::from(ty - region.y() - y),
+        );
+        let length = dx.hypot(dy).max(1.0);
+        let (ux, uy) = (dx / length, dy / length);
+        let head = f64::from(w) / 6.0;
+        let (ex, ey) = (cx + dx as i32, cy + dy as i32);
+        let (bx, by) = (f64::from(ex) - ux * head, f64::from(ey) - uy * head);
+        let (px, py) = (-uy * head, ux * head);
+        for i in -1..=1 {
+            let (ox, oy) = ((-uy * f64::from(i)) as i32, (ux * f64::from(i)) as i32);
+            canvas
+                .draw_line((cx + ox, cy + oy), (ex + ox, ey + oy))
+                .unwrap();
+            let left = ((bx + px) as i32 + ox, (by + py) as i32 + oy);
+            let right = ((bx - px) as i32 + ox, (by - py) as i32 + oy);
+            canvas.draw_line((ex + ox, ey + oy), left).unwrap();
+            canvas.draw_line((ex + ox, ey + oy), right).unwrap();
+        }
+
+        canvas.set_draw_color(prev_color);
+    }
+
+    /// Paints an overview of the whole level in the top right corner, along
+    /// with a frame around the visible region.
+    fn paint_minimap(&mut self, canvas: &mut Canvas<Window>, level: &Level, region: &Rect) {
+        let (cols, rows) = level.extents();
+        let cell = cmp::max(
+            1,
+            cmp::min(
+                self.minimap_size / cols as u32,
+                self.minimap_size / rows as u32,
+            ),
+        );
+        let margin = 8;
+        let topology = level.rules().topology();
+        let shift = (topology.column_offset(1) * f64::from(cell)) as u32;
+        let (w, h) = (cell * cols as u32 + shift, cell * rows as u32);
+
+        // Leave the minimap out when the window is too small to hold it
+        let Some(x0) = self.screen_size.0.checked_sub(margin + w) else {
+            return;
+        };
+        if margin + h > self.screen_size.1 {
+            return;
+        }
+        let (x0, y0) = (x0 as i32, margin as i32);
+
+        let prev_color = canvas.draw_color();
+        let prev_blend = canvas.blend_mode();
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 160));
+        canvas.fill_rect(Rect::new(x0, y0, w, h)).unwrap();
+
+        for r in 0..rows {
+            let shift = (topology.column_offset(r) * f64::from(cell)) as i32;
+            for c in 0..cols {
+                let pos = Position::new(r, c);
+                let color = if level.is_wall(&pos) {
+                    Color::RGB(128, 128, 128)
+                } else if level.is_box(&pos) && level.is_square(&pos) {
+                    Color::RGB(64, 192, 64)
+                } else if level.is_box(&pos) {
+                    Color::RGB(160, 96, 32)
+                } else if level.is_player(&pos) {
+                    Color::RGB(64, 128, 255)
+                } else if level.is_square(&pos) {
+                    Color::RGB(255, 224, 64)
+                } else {
+                    continue;
+                };
+                canvas.set_draw_color(color);
+                let x = x0 + c * cell as i32 + shift;
+                let y = y0 + r * cell as i32;
+                canvas.fill_rect(Rect::new(x, y, cell, cell)).unwrap();
+            }
+        }
+
+        // Frame the region that is visible on the screen
+        let to_minimap = |px: i32, tile: u32| px * cell as i32 / tile as i32;
+        let (tw, teh) = (self.tileset().width(), self.tileset().effective_height());
+        let frame = Rect::new(
+            x0 + to_minimap(region.x(), tw),
+            y0 + to_minimap(region.y(), teh),
+            cmp::max(1, to_minimap(region.width() as i32, tw) as u32),
+            cmp::max(1, to_minimap(region.height() as i32, teh) as u32),
+        );
+        canvas.set_draw_color(self.bar_text_color);
+        canvas.draw_rect(frame).unwrap();
+
+        canvas.set_blend_mode(prev_blend);
+        canvas.set_draw_color(prev_color);
+    }
+
     /// Paints the status bar
-    fn paint_status_bar(&mut self, canvas: &mut Canvas<Window>, level: &Level) {
+    fn paint_status_bar(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        level: &Level,
+        challenge: &Challenge,
+    ) {
         let prev_color = canvas.draw_color();
         canvas.set_draw_color(self.bar_color);
         let rect = Rect::new(
@@ -163,9 +396,18 @@ impl<'a> Painter<'a> {
         canvas.set_draw_color(prev_color);
 
         // Paints the number of moves
-        let s = format!("# moves: {}", level.get_steps());
+        let s = if level.is_reverse() {
+            format!("# moves: {} (reverse)", level.get_steps())
+        } else {
+            format!("# moves: {}", level.get_steps())
+        };
         self.paint_status_text(canvas, &s, StatusBarLocation::FlushLeft);
 
+        // Paints the state of the challenge
+        if let Some(s) = get_challenge_text(level, challenge) {
+            self.paint_status_text(canvas, &s, StatusBarLocation::Centered);
+        }
+
         // Paints the level's title
         self.paint_status_text(canvas, level.title(), StatusBarLocation::FlushRight);
     }
@@ -189,6 +431,10 @@ impl<'a> Painter<'a> {
             StatusBarLocation::FlushLeft => {
                 (margin as i32, (self.screen_size.1 - margin - h) as i32)
             }
+            StatusBarLocation::Centered => (
+                (self.screen_size.0 as i32 - w as i32) / 2,
+                (self.screen_size.1 - margin - h) as i32,
+            ),
             StatusBarLocation::FlushRight => (
                 (self.screen_size.0 - margin - w) as i32,
                 (self.screen_size.1 - margin - h) as i32,
@@ -211,22 +457,44 @@ impl<'a> Painter<'a> {
             self.tileset().width(),
             self.tileset().height(),
         ));
-        canvas
-            .copy(self.tileset().texture(), tile_rect, target_rect)
-            .unwrap();
+        let color = self.tileset().color_mod(tile);
+        let texture = self.selector.select_mut().texture_mut();
+        if let Some(color) = color {
+            texture.set_color_mod(color.r, color.g, color.b);
+        }
+        canvas.copy(texture, tile_rect, target_rect).unwrap();
+        if color.is_some() {
+            texture.set_color_mod(255, 255, 255);
+        }
     }
 
-    /// Returns the size of the drawing scaled to fit onto the screen.
-    fn get_scaled_rendering_size(&self, level: &Level) -> (u32, u32) {
-        let render_size = self.tileset().get_rendering_size(level.extents());
+    /// Returns the ratio by which the full-size image of a level is scaled
+    /// down to fit onto the screen.
+    fn get_fit_ratio(&self, level: &Level) -> f64 {
+        let topology = level.rules().topology();
+        let render_size = self.tileset().get_rendering_size(level.extents(), topology);
         let width_ratio = f64::from(self.screen_size.0) / f64::from(render_size.0);
         let h = self.screen_size.1 - self.bar_height;
         let height_ratio = f64::from(h) / f64::from(render_size.1);
-        let ratio = f64::min(1.0, f64::min(width_ratio, height_ratio));
+        f64::min(1.0, f64::min(width_ratio, height_ratio))
+    }
 
-        let scale = |sz: u32| (ratio * f64::from(sz)).floor() as u32;
+    /// Returns the part of the full-size image that fits onto the screen at the
+    /// given scale, centered as much as possible on the given column and row.
+    fn get_visible_region(&self, fullsize: (u32, u32), scale: f64, center: (f64, f64)) -> Rect {
+        let view = (self.screen_size.0, self.screen_size.1 - self.bar_height);
+        let size = |view: u32, full: u32| {
+            let sz = (f64::from(view) / scale).floor() as u32;
+            cmp::max(1, cmp::min(full, sz))
+        };
+        let (w, h) = (size(view.0, fullsize.0), size(view.1, fullsize.1));
 
-        (scale(render_size.0), scale(render_size.1))
+        let (cx, cy) = self.tileset().get_pixel_coordinates(center);
+        let origin = |c: f64, sz: u32, full: u32| {
+            let max = f64::from(full.saturating_sub(sz));
+            (c - f64::from(sz) / 2.0).round().clamp(0.0, max) as i32
+        };
+        Rect::new(origin(cx, w, fullsize.0), origin(cy, h, fullsize.1), w, h)
     }
 
     /// Returns the Rect of an image of given dimensions so that it's centered on the screen.
@@ -241,12 +509,39 @@ impl<'a> Painter<'a> {
     }
 }
 
+/// Returns the text describing the state of the challenge, if any.
+fn get_challenge_text(level: &Level, challenge: &Challenge) -> Option<String> {
+    let mut parts = Vec::new();
+    match challenge.status(level) {
+        Status::TimeUp => parts.push(String::from("Time's up! R: retry, N: skip")),
+        Status::OutOfMoves => parts.push(String::from("Out of moves! R: retry, N: skip")),
+        Status::Playing | Status::Completed => {
+            if let Some(time) = challenge.remaining_time() {
+                let secs = time.as_secs();
+                parts.push(format!("time: {}:{:02}", secs / 60, secs % 60));
+            }
+            if let Some(moves) = challenge.remaining_moves(level) {
+                parts.push(format!("moves left: {}", moves));
+            }
+        }
+    }
+    if challenge.is_marathon() {
+        parts.push(format!("score: {}", challenge.score()));
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("   "))
+    }
+}
+
 /// Returns the shadow flags for a particular position in the given level.
 fn get_shadow_flags(level: &Level, pos: &Position) -> ShadowFlags {
-    let north = pos.neighbor(Direction::Up);
-    let south = pos.neighbor(Direction::Down);
-    let west = pos.neighbor(Direction::Left);
-    let east = pos.neighbor(Direction::Right);
+    let north = pos.offset(-1, 0);
+    let south = pos.offset(1, 0);
+    let west = pos.offset(0, -1);
+    let east = pos.offset(0, 1);
 
     let mut flags = ShadowFlags::empty();
     if level.is_wall(&north) {
@@ -261,22 +556,22 @@ fn get_shadow_flags(level: &Level, pos: &Position) -> ShadowFlags {
     if level.is_wall(&east) {
         flags |= ShadowFlags::E_EDGE;
     }
-    if level.is_wall(&north.neighbor(Direction::Right))
+    if level.is_wall(&north.offset(0, 1))
         && !flags.intersects(ShadowFlags::N_EDGE | ShadowFlags::E_EDGE)
     {
         flags |= ShadowFlags::NE_CORNER;
     }
-    if level.is_wall(&north.neighbor(Direction::Left))
+    if level.is_wall(&north.offset(0, -1))
         && !flags.intersects(ShadowFlags::N_EDGE | ShadowFlags::W_EDGE)
     {
         flags |= ShadowFlags::NW_CORNER;
     }
-    if level.is_wall(&south.neighbor(Direction::Right))
+    if level.is_wall(&south.offset(0, 1))
         && !flags.intersects(ShadowFlags::S_EDGE | ShadowFlags::E_EDGE)
     {
         flags |= ShadowFlags::SE_CORNER;
     }
-    if level.is_wall(&south.neighbor(Direction::Left))
+    if level.is_wall(&south.offset(0, -1))
         && !flags.intersects(ShadowFlags::S_EDGE | ShadowFlags::W_EDGE)
     {
         flags |= ShadowFlags::SW_CORNER;
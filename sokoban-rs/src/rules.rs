@@ -0,0 +1,218 @@
+// This file is part of sokoban-rs
+// Copyright 2015 Sébastien Watteau
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The rules of the supported Sokoban variants.
+
+use crate::game::{Direction, Level, Position};
+
+/// A direction along with the row and column offsets of the neighbor lying in
+/// that direction.
+type Step = (Direction, (i32, i32));
+
+/// The neighbors of a cell on a square grid.
+const SQUARE_NEIGHBORS: [Step; 4] = [
+    (Direction::Up, (-1, 0)),
+    (Direction::Down, (1, 0)),
+    (Direction::Left, (0, -1)),
+    (Direction::Right, (0, 1)),
+];
+
+/// The neighbors of a cell in an even row of a hexagonal grid.
+const EVEN_ROW_NEIGHBORS: [Step; 6] = [
+    (Direction::Left, (0, -1)),
+    (Direction::Right, (0, 1)),
+    (Direction::UpLeft, (-1, -1)),
+    (Direction::UpRight, (-1, 0)),
+    (Direction::DownLeft, (1, -1)),
+    (Direction::DownRight, (1, 0)),
+];
+
+/// The neighbors of a cell in an odd row of a hexagonal grid. Odd rows are
+/// shifted right, so their diagonal neighbors are one column further right
+/// than those of even rows.
+const ODD_ROW_NEIGHBORS: [Step; 6] = [
+    (Direction::Left, (0, -1)),
+    (Direction::Right, (0, 1)),
+    (Direction::UpLeft, (-1, 0)),
+    (Direction::UpRight, (-1, 1)),
+    (Direction::DownLeft, (1, 0)),
+    (Direction::DownRight, (1, 1)),
+];
+
+/// Represents the shape of the grid on which a level is played.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Square cells with four neighbors
+    Square,
+    /// Hexagonal cells with six neighbors, odd rows being shifted right by
+    /// half a cell
+    Hexagonal,
+}
+
+impl Topology {
+    /// Returns the directions in which the player can move.
+    pub fn directions(self) -> &'static [Direction] {
+        match self {
+            Topology::Square => &[
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ],
+            Topology::Hexagonal => &[
+                Direction::Left,
+                Direction::Right,
+                Direction::UpLeft,
+                Direction::UpRight,
+                Direction::DownLeft,
+                Direction::DownRight,
+            ],
+        }
+    }
+
+    /// Returns the neighbors of a cell in the given row.
+    fn neighbors(self, row: i32) -> &'static [Step] {
+        match self {
+            Topology::Square => &SQUARE_NEIGHBORS,
+            Topology::Hexagonal if row.rem_euclid(2) == 0 => &EVEN_ROW_NEIGHBORS,
+            Topology::Hexagonal => &ODD_ROW_NEIGHBORS,
+        }
+    }
+
+    /// Returns the position that is next to the given one in the given
+    /// direction, or `None` if the grid has no neighbor in that direction.
+    pub fn neighbor(self, pos: &Position, dir: Direction) -> Option<Position> {
+        self.neighbors(pos.row())
+            .iter()
+            .find(|&&(d, _)| d == dir)
+            .map(|&(_, (rows, cols))| pos.offset(rows, cols))
+    }
+
+    /// Returns the direction matching a character of a solution written for
+    /// this grid, in the LURD format on a square grid.
+    pub fn parse_move(self, c: char) -> Option<Direction> {
+        match self {
+            Topology::Square => Direction::from_lurd(c),
+            Topology::Hexagonal => Direction::from_hex(c),
+        }
+    }
+
+    /// Returns the fraction of a column by which the given row is shifted right
+    /// when drawn.
+    pub fn column_offset(self, row: i32) -> f64 {
+        match self {
+            Topology::Square => 0.0,
+            Topology::Hexagonal => 0.5 * f64::from(row.rem_euclid(2)),
+        }
+    }
+}
+
+/// Describes the rules of a Sokoban variant.
+pub trait Rules: Sync {
+    /// Returns the name of the variant.
+    fn name(&self) -> &'static str;
+
+    /// Returns the shape of the grid.
+    fn topology(&self) -> Topology {
+        Topology::Square
+    }
+
+    /// Returns the number of colors boxes and squares may have besides the
+    /// default one.
+    fn colors(&self) -> u8 {
+        0
+    }
+
+    /// Returns true if a box of the given color may cover a square of the
+    /// given color.
+    fn fits(&self, _box_color: u8, _square_color: u8) -> bool {
+        true
+    }
+
+    /// Returns true if the level is completed, i.e. every square is covered
+    /// by a box that fits it.
+    fn is_completed(&self, level: &Level) -> bool {
+        level.squares().all(|pos| {
+            level.is_box(pos) && self.fits(level.box_color(pos), level.square_color(pos))
+        })
+    }
+}
+
+/// The classic rules.
+pub struct Classic;
+
+impl Rules for Classic {
+    fn name(&self) -> &'static str {
+        "classic"
+    }
+}
+
+/// Boxes and squares have colors, and every square must be covered by a box
+/// of the same color.
+pub struct Colored;
+
+impl Colored {
+    /// The number of colors besides the default one.
+    pub const COLORS: u8 = 6;
+}
+
+impl Rules for Colored {
+    fn name(&self) -> &'static str {
+        "colored"
+    }
+
+    fn colors(&self) -> u8 {
+        Colored::COLORS
+    }
+
+    fn fits(&self, box_color: u8, square_color: u8) -> bool {
+        box_color == square_color
+    }
+}
+
+/// The classic rules played on a hexagonal grid.
+pub struct Hexoban;
+
+impl Rules for Hexoban {
+    fn name(&self) -> &'static str {
+        "hexoban"
+    }
+
+    fn topology(&self) -> Topology {
+        Topology::Hexagonal
+    }
+}
+
+/// Represents a variant that can be selected from the command line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Variant {
+    /// Classic Sokoban
+    Classic,
+    /// Colored boxes that must reach squares of the same color
+    Colored,
+    /// Sokoban on a hexagonal grid
+    Hexoban,
+}
+
+impl Variant {
+    /// Returns the rules of this variant.
+    pub fn rules(self) -> &'static dyn Rules {
+        match self {
+            Variant::Classic => &Classic,
+            Variant::Colored => &Colored,
+            Variant::Hexoban => &Hexoban,
+        }
+    }
+}
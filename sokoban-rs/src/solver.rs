@@ -0,0 +1,292 @@
+// This file is part of sokoban-rs
+// Copyright 2015 Sébastien Watteau
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A breadth-first search over box moves, used to give hints to the player.
+
+use crate::game::{Direction, Level, Position};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// The maximum number of states explored before giving up.
+const MAX_STATES: usize = 200_000;
+
+/// The longest time spent searching before giving up, so that asking for a
+/// hint never freezes the game.
+const TIME_LIMIT: Duration = Duration::from_millis(250);
+
+/// Represents the move of a box in a given direction.
+///
+/// In a reverse level the box is pulled, otherwise it is pushed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// The position of the box to move
+    pub position: Position,
+    /// The direction in which the box moves
+    pub direction: Direction,
+}
+
+/// A box with its color.
+type ColoredBox = (Position, u8);
+
+/// A node of the search: the sorted boxes and the normalized player position,
+/// i.e. the smallest position reachable by the player.
+type State = (Vec<ColoredBox>, Position);
+
+/// Returns the first box move of a shortest solution from the current state
+/// of the level, or `None` if no solution was found within `TIME_LIMIT`.
+pub fn hint(level: &Level) -> Option<Hint> {
+    if level.is_completed() {
+        return None;
+    }
+
+    let start = Instant::now();
+    let solver = Solver::new(level);
+    let mut boxes: Vec<ColoredBox> = level
+        .boxes()
+        .map(|&pos| (pos, level.box_color(&pos)))
+        .collect();
+    boxes.sort();
+    let player = level.player();
+
+    let mut visited: HashSet<State> = HashSet::new();
+    let mut queue: VecDeque<(Vec<ColoredBox>, Position, Option<Hint>)> = VecDeque::new();
+    let (_, norm) = solver.reachable(&boxes, player);
+    visited.insert((boxes.clone(), norm));
+    queue.push_back((boxes, player, None));
+
+    while let Some((boxes, player, first)) = queue.pop_front() {
+        if start.elapsed() > TIME_LIMIT {
+            return None;
+        }
+        let (reachable, _) = solver.reachable(&boxes, player);
+        for (index, &(position, color)) in boxes.iter().enumerate() {
+            for &direction in level.directions() {
+                let Some((target, next_player)) =
+                    solver.box_move(&boxes, &reachable, position, direction)
+                else {
+                    continue;
+                };
+
+                let mut next_boxes = boxes.clone();
+                next_boxes[index] = (target, color);
+                next_boxes.sort();
+
+                let first = first.or(Some(Hint {
+                    position,
+                    direction,
+                }));
+                if solver.is_solved(&next_boxes) {
+                    return first;
+                }
+
+                let (_, norm) = solver.reachable(&next_boxes, next_player);
+                if visited.insert((next_boxes.clone(), norm)) {
+                    if visited.len() > MAX_STATES {
+                        return None;
+                    }
+                    queue.push_back((next_boxes, next_player, first));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Holds the static parts of a level that are needed by the search.
+struct Solver<'a> {
+    level: &'a Level,
+    /// The squares a box may be pushed to without becoming stuck
+    alive: HashSet<Position>,
+    /// The target squares with their colors
+    squares: Vec<ColoredBox>,
+}
+
+impl<'a> Solver<'a> {
+    fn new(level: &'a Level) -> Self {
+        let squares: Vec<ColoredBox> = level
+            .squares()
+            .map(|&pos| (pos, level.square_color(&pos)))
+            .collect();
+        let mut solver = Solver {
+            level,
+            alive: HashSet::new(),
+            squares,
+        };
+        solver.alive = solver.alive_squares();
+        solver
+    }
+
+    /// Returns true if every target square is covered by a box of the right color.
+    fn is_solved(&self, boxes: &[ColoredBox]) -> bool {
+        let rules = self.level.rules();
+        self.squares.iter().all(|&(pos, color)| {
+            find_box(boxes, &pos).is_some_and(|i| rules.fits(boxes[i].1, color))
+        })
+    }
+
+    /// Returns true if the position is inside the level and is not a wall.
+    fn is_floor(&self, pos: &Position) -> bool {
+        let (cols, rows) = self.level.extents();
+        pos.row() >= 0
+            && pos.row() < rows
+            && pos.column() >= 0
+            && pos.column() < cols
+            && !self.level.is_wall(pos)
+    }
+
+    /// Returns true if the position is a floor without any box on it.
+    fn is_free(&self, boxes: &[ColoredBox], pos: &Position) -> bool {
+        self.is_floor(pos) && find_box(boxes, pos).is_none()
+    }
+
+    /// Returns the set of positions the player can walk to, along with the
+    /// smallest of them.
+    fn reachable(&self, boxes: &[ColoredBox], player: Position) -> (HashSet<Position>, Position) {
+        let mut seen = HashSet::new();
+        let mut stack = vec![player];
+        let mut min = player;
+        seen.insert(player);
+        while let Some(pos) = stack.pop() {
+            if pos < min {
+                min = pos;
+            }
+            for &dir in self.level.directions() {
+                if let Some(next) = self.level.neighbor(&pos, dir)
+                    && self.is_free(boxes, &next)
+                    && seen.insert(next)
+                {
+                    stack.push(next);
+                }
+            }
+        }
+        (seen, min)
+    }
+
+    /// Returns the new box position and the new player position if the box at
+    /// `position` can be moved in the given direction.
+    fn box_move(
+        &self,
+        boxes: &[ColoredBox],
+        reachable: &HashSet<Position>,
+        position: Position,
+        direction: Direction,
+    ) -> Option<(Position, Position)> {
+        let target = self.level.neighbor(&position, direction)?;
+        if self.level.is_reverse() {
+            // The player stands on the target and steps away from the box
+            let next_player = self.level.neighbor(&target, direction)?;
+            if reachable.contains(&target) && self.is_free(boxes, &next_player) {
+                return Some((target, next_player));
+            }
+        } else {
+            // The player stands behind the box and ends up in its place
+            let behind = self.level.neighbor(&position, direction.opposite())?;
+            if reachable.contains(&behind)
+                && self.is_free(boxes, &target)
+                && self.alive.contains(&target)
+            {
+                return Some((target, position));
+            }
+        }
+        None
+    }
+
+    /// Returns the positions from which a single box can still reach a square.
+    ///
+    /// In a reverse level boxes can always be pulled back, so every floor
+    /// position is considered alive.
+    fn alive_squares(&self) -> HashSet<Position> {
+        let mut alive = HashSet::new();
+        if self.level.is_reverse() {
+            let (cols, rows) = self.level.extents();
+            for r in 0..rows {
+                for c in 0..cols {
+                    let pos = Position::new(r, c);
+                    if self.is_floor(&pos) {
+                        alive.insert(pos);
+                    }
+                }
+            }
+            return alive;
+        }
+
+        // Pull a box away from every square
+        let mut stack: Vec<Position> = self.squares.iter().map(|&(pos, _)| pos).collect();
+        alive.extend(stack.iter().cloned());
+        while let Some(pos) = stack.pop() {
+            for &dir in self.level.directions() {
+                let Some(from) = self.level.neighbor(&pos, dir) else {
+                    continue;
+                };
+                if let Some(player) = self.level.neighbor(&from, dir)
+                    && self.is_floor(&from)
+                    && self.is_floor(&player)
+                    && alive.insert(from)
+                {
+                    stack.push(from);
+                }
+            }
+        }
+        alive
+    }
+}
+
+/// Returns the index of the box at the given position in a sorted list of boxes.
+fn find_box(boxes: &[ColoredBox], pos: &Position) -> Option<usize> {
+    boxes.binary_search_by_key(pos, |&(p, _)| p).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hint_pushes_box_towards_square() {
+        let level: Level = "#####\n#@$.#\n#####".parse().unwrap();
+        assert_eq!(
+            hint(&level),
+            Some(Hint {
+                position: Position::new(1, 2),
+                direction: Direction::Right,
+            })
+        );
+    }
+
+    #[test]
+    fn no_hint_when_solved() {
+        let level: Level = "####\n#@*#\n####".parse().unwrap();
+        assert_eq!(hint(&level), None);
+    }
+
+    #[test]
+    fn no_hint_when_deadlocked() {
+        let level: Level = "######\n#$  .#\n# @  #\n######".parse().unwrap();
+        assert_eq!(hint(&level), None);
+    }
+
+    #[test]
+    fn hint_pulls_box_in_reverse_level() {
+        let level: Level = "######\n#@$ .#\n######".parse().unwrap();
+        let level = level.reversed().unwrap();
+        assert_eq!(
+            hint(&level),
+            Some(Hint {
+                position: Position::new(1, 4),
+                direction: Direction::Left,
+            })
+        );
+    }
+}
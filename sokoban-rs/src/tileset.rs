@@ -13,8 +13,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{game::Position, shadow::ShadowFlags};
-use sdl2::{rect::Rect, render::Texture};
+use crate::{
+    game::Position,
+    rules::{Colored, Topology},
+    shadow::ShadowFlags,
+};
+use sdl2::{pixels::Color, rect::Rect, render::Texture};
 use std::cmp;
 
 /// Represents a kind of tile.
@@ -26,14 +30,28 @@ pub enum Tile {
     Wall,
     /// Rock tile
     Rock,
+    /// Rock tile tinted with the given color
+    ColoredRock(u8),
     /// Target square tile
     Square,
+    /// Target square tile tinted with the given color
+    ColoredSquare(u8),
     /// Player tile
     Player,
     /// Shadow tile
     Shadow(ShadowFlags),
 }
 
+/// The colors used to tell apart boxes and squares in colored levels.
+const PALETTE: [Color; Colored::COLORS as usize] = [
+    Color::RGB(255, 96, 96),
+    Color::RGB(96, 160, 255),
+    Color::RGB(96, 255, 96),
+    Color::RGB(255, 255, 96),
+    Color::RGB(224, 96, 255),
+    Color::RGB(96, 255, 255),
+];
+
 pub struct Tileset<'a> {
     texture: Texture<'a>,
     width: u32,
@@ -64,6 +82,11 @@ impl<'a> Tileset<'a> {
         &self.texture
     }
 
+    /// Returns the associated texture for modification
+    pub fn texture_mut(&mut self) -> &mut Texture<'a> {
+        &mut self.texture
+    }
+
     /// Returns the width of a tile.
     pub fn width(&self) -> u32 {
         self.width
@@ -89,8 +112,8 @@ impl<'a> Tileset<'a> {
         match tile {
             Tile::Floor => Some((0, 0)),
             Tile::Wall => Some((0, 2)),
-            Tile::Rock => Some((2, 0)),
-            Tile::Square => Some((1, 0)),
+            Tile::Rock | Tile::ColoredRock(_) => Some((2, 0)),
+            Tile::Square | Tile::ColoredSquare(_) => Some((1, 0)),
             Tile::Player => Some((3, 0)),
             Tile::Shadow(ShadowFlags::N_EDGE) => Some((4, 0)),
             Tile::Shadow(ShadowFlags::S_EDGE) => Some((5, 0)),
@@ -104,17 +127,41 @@ impl<'a> Tileset<'a> {
         }
     }
 
+    /// Returns the color the tile is tinted with, if any.
+    pub fn color_mod(&self, tile: Tile) -> Option<Color> {
+        match tile {
+            Tile::ColoredRock(color) | Tile::ColoredSquare(color) if color > 0 => {
+                PALETTE.get(color as usize - 1).copied()
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the top-left corner coordinates of the tile corresponding
     /// to the given position.
-    pub fn get_coordinates(&self, pos: &Position) -> (i32, i32) {
-        let x = self.width as i32 * pos.column();
+    pub fn get_coordinates(&self, pos: &Position, topology: Topology) -> (i32, i32) {
+        let shift = topology.column_offset(pos.row()) * f64::from(self.width);
+        let x = self.width as i32 * pos.column() + shift as i32;
         let y = self.effective_height as i32 * pos.row();
         (x, y)
     }
 
+    /// Returns the pixel coordinates of the given fractional column and row,
+    /// measured on the floor tiles.
+    pub fn get_pixel_coordinates(&self, (col, row): (f64, f64)) -> (f64, f64) {
+        let x = col * f64::from(self.width);
+        let y = row * f64::from(self.effective_height) + f64::from(self.offset);
+        (x, y)
+    }
+
     /// Returns the full size needed to draw a level of the given dimensions.
-    pub fn get_rendering_size(&self, extents: (i32, i32)) -> (u32, u32) {
-        let width = extents.0 as u32 * self.width;
+    pub fn get_rendering_size(&self, extents: (i32, i32), topology: Topology) -> (u32, u32) {
+        let shift = if extents.1 > 1 {
+            topology.column_offset(1) * f64::from(self.width)
+        } else {
+            0.0
+        };
+        let width = extents.0 as u32 * self.width + shift as u32;
         let height = if extents.1 > 0 {
             self.height + (extents.1 - 1) as u32 * self.effective_height
         } else {
@@ -136,6 +183,8 @@ impl<'a> Tileset<'a> {
 pub struct TilesetSelector<'a> {
     /// The extents of the current level
     extents: (i32, i32),
+    /// The current zoom factor
+    zoom: f64,
     /// The big tileset
     big_set: Tileset<'a>,
     /// The small tileset
@@ -149,21 +198,40 @@ impl<'a> TilesetSelector<'a> {
     pub fn new(big_set: Tileset<'a>, small_set: Tileset<'a>) -> Self {
         TilesetSelector {
             extents: (0, 0),
+            zoom: 1.0,
             big_set,
             small_set,
         }
     }
 
-    /// Resets the selector with the given extents.
-    pub fn reset(&mut self, extents: (i32, i32)) {
+    /// Resets the selector with the given extents and zoom factor.
+    pub fn reset(&mut self, extents: (i32, i32), zoom: f64) {
         self.extents = extents;
+        self.zoom = zoom;
     }
 
+    /// Returns the small tileset when a level is so large that its tiles would
+    /// be scaled down anyway. Zooming in raises the threshold accordingly.
     pub fn select(&self) -> &Tileset<'_> {
-        if cmp::max(self.extents.0, self.extents.1) > TilesetSelector::THRESHOLD {
+        if self.is_small() {
             &self.small_set
         } else {
             &self.big_set
         }
     }
+
+    /// Returns the selected tileset for modification.
+    pub fn select_mut(&mut self) -> &mut Tileset<'a> {
+        if self.is_small() {
+            &mut self.small_set
+        } else {
+            &mut self.big_set
+        }
+    }
+
+    /// Returns true if the small tileset is selected.
+    fn is_small(&self) -> bool {
+        let threshold = f64::from(TilesetSelector::THRESHOLD) * self.zoom;
+        f64::from(cmp::max(self.extents.0, self.extents.1)) > threshold
+    }
 }